@@ -0,0 +1,154 @@
+//! An example plugin with a variant of both parts of 2024 day 1, to be loaded
+//! with `--plugins`:
+//!
+//! ```text
+//! cargo build --release --workspace
+//! cargo run --release -- compare year=2024 day=1 --plugins=target/release
+//! ```
+//!
+//! Plugins only share a C ABI with the runner, so this crate declares its own
+//! copy of the types in `src/util/plugin.rs` instead of depending on the runner.
+
+use std::collections::HashMap;
+use std::ffi::{c_char, CStr};
+use std::panic;
+use std::slice;
+use std::str;
+
+const ABI_VERSION: u32 = 1;
+
+type SolveFn = unsafe extern "C" fn(*const u8, usize, *mut u8, usize) -> isize;
+
+#[repr(C)]
+pub struct Solution {
+    year: u16,
+    day: u8,
+    part: u8,
+    name: *const c_char,
+    solve: SolveFn,
+}
+
+// The names are static strings, so sharing them between threads is fine
+unsafe impl Sync for Solution {}
+
+const NAME: &CStr = c"example";
+
+static SOLUTIONS: [Solution; 2] = [
+    Solution {
+        year: 2024,
+        day: 1,
+        part: 1,
+        name: NAME.as_ptr(),
+        solve: part_1,
+    },
+    Solution {
+        year: 2024,
+        day: 1,
+        part: 2,
+        name: NAME.as_ptr(),
+        solve: part_2,
+    },
+];
+
+#[no_mangle]
+pub extern "C" fn aoc_abi_version() -> u32 {
+    ABI_VERSION
+}
+
+/// # Safety
+///
+/// `count` must point to a writable `usize`.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solutions(count: *mut usize) -> *const Solution {
+    *count = SOLUTIONS.len();
+    SOLUTIONS.as_ptr()
+}
+
+unsafe extern "C" fn part_1(
+    input: *const u8,
+    len: usize,
+    answer: *mut u8,
+    capacity: usize,
+) -> isize {
+    call(solve_part_1, input, len, answer, capacity)
+}
+
+unsafe extern "C" fn part_2(
+    input: *const u8,
+    len: usize,
+    answer: *mut u8,
+    capacity: usize,
+) -> isize {
+    call(solve_part_2, input, len, answer, capacity)
+}
+
+/// Runs a solver on the input and writes its answer, returning -1 if the input
+/// isn't UTF-8, the solver panics or the answer doesn't fit. A panic must never
+/// unwind into the runner.
+unsafe fn call(
+    solve: fn(&str) -> Option<u64>,
+    input: *const u8,
+    len: usize,
+    answer: *mut u8,
+    capacity: usize,
+) -> isize {
+    let Ok(input) = str::from_utf8(slice::from_raw_parts(input, len)) else {
+        return -1;
+    };
+    let Ok(Some(result)) = panic::catch_unwind(|| solve(input)) else {
+        return -1;
+    };
+
+    let result = result.to_string();
+    if result.len() > capacity {
+        return -1;
+    }
+    slice::from_raw_parts_mut(answer, capacity)[..result.len()].copy_from_slice(result.as_bytes());
+    result.len() as isize
+}
+
+fn lists(input: &str) -> Option<(Vec<u64>, Vec<u64>)> {
+    input
+        .lines()
+        .map(|line| {
+            let mut ids = line.split_whitespace().map(|id| id.parse::<u64>().ok());
+            Some((ids.next()??, ids.next()??))
+        })
+        .collect()
+}
+
+fn solve_part_1(input: &str) -> Option<u64> {
+    let (mut left, mut right) = lists(input)?;
+    left.sort_unstable();
+    right.sort_unstable();
+
+    Some(left.iter().zip(&right).map(|(a, b)| a.abs_diff(*b)).sum())
+}
+
+fn solve_part_2(input: &str) -> Option<u64> {
+    let (left, right) = lists(input)?;
+    let mut counts = HashMap::new();
+    for id in right {
+        *counts.entry(id).or_insert(0) += 1;
+    }
+
+    Some(
+        left.iter()
+            .map(|id| id * counts.get(id).unwrap_or(&0))
+            .sum(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{solve_part_1, solve_part_2};
+
+    const EXAMPLE: &str = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(solve_part_1(EXAMPLE), Some(11));
+        assert_eq!(solve_part_2(EXAMPLE), Some(31));
+        assert_eq!(solve_part_1("3 x"), None);
+    }
+}
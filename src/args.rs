@@ -0,0 +1,283 @@
+use crate::config::Settings;
+use crate::util::runner::INPUTS;
+use crate::util::trace::Level;
+use serde::Deserialize;
+use std::str::FromStr;
+use std::time::Duration;
+
+pub struct Args {
+    pub command: String,
+    year: Option<u16>,
+    day: Option<u8>,
+    pub variant: Option<String>,
+    pub input: Option<String>,
+    inputs: Option<String>,
+    pub plugins: Option<String>,
+    pub limit: Option<Duration>,
+    pub timeout: Option<Duration>,
+    iterations: Option<u32>,
+    format: Option<Format>,
+    pub profile: Option<String>,
+    pub trace: Level,
+    pub explain: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Text,
+    Json,
+}
+
+impl Args {
+    pub fn parse(args: impl Iterator<Item = String>) -> Args {
+        let mut result = Args {
+            command: "run".to_string(),
+            year: None,
+            day: None,
+            variant: None,
+            input: None,
+            inputs: None,
+            plugins: None,
+            limit: None,
+            timeout: None,
+            iterations: None,
+            format: None,
+            profile: None,
+            trace: Level::Off,
+            explain: false,
+        };
+
+        for arg in args {
+            if let Some(year) = arg.strip_prefix("year=") {
+                result.year = Some(parse_year(year));
+            } else if let Some(day) = arg.strip_prefix("day=") {
+                result.day = Some(parse_day(day));
+            } else if let Some(variant) = arg.strip_prefix("--variant=") {
+                result.variant = Some(variant.to_string());
+            } else if let Some(input) = arg.strip_prefix("--input=") {
+                result.input = Some(input.to_string());
+            } else if let Some(inputs) = arg.strip_prefix("--inputs=") {
+                result.inputs = Some(inputs.to_string());
+            } else if let Some(plugins) = arg.strip_prefix("--plugins=") {
+                result.plugins = Some(plugins.to_string());
+            } else if let Some(limit) = arg.strip_prefix("--limit=") {
+                result.limit = Some(parse_duration(limit));
+            } else if let Some(timeout) = arg.strip_prefix("--timeout=") {
+                result.timeout = Some(parse_duration(timeout));
+            } else if let Some(iterations) = arg.strip_prefix("--iterations=") {
+                result.iterations = Some(parse_iterations(iterations));
+            } else if let Some(format) = arg.strip_prefix("--format=") {
+                result.format = Some(format.parse().unwrap_or_else(|err| panic!("{}", err)));
+            } else if let Some(profile) = arg.strip_prefix("--profile=") {
+                result.profile = Some(profile.to_string());
+            } else if arg == "--trace" {
+                result.trace = Level::Trace;
+            } else if let Some(level) = arg.strip_prefix("--trace=") {
+                result.trace = level.parse().unwrap_or_else(|err| panic!("{}", err));
+            } else if arg == "--explain" {
+                result.explain = true;
+            } else if arg.starts_with("--") || arg.contains('=') {
+                panic!("Unknown argument: {}", arg);
+            } else {
+                result.command = arg;
+            }
+        }
+
+        result
+    }
+
+    pub fn year(&self) -> u16 {
+        self.year.expect("Please provide a year argument")
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day.expect("Please provide a day argument")
+    }
+
+    /// The directory inputs are read from, unless a single file is given with `--input`.
+    pub fn inputs(&self) -> &str {
+        self.inputs.as_deref().unwrap_or(INPUTS)
+    }
+
+    pub fn iterations(&self) -> u32 {
+        self.iterations.unwrap_or(1)
+    }
+
+    pub fn format(&self) -> Format {
+        self.format.unwrap_or(Format::Text)
+    }
+
+    /// Fills in everything that wasn't given on the command line from the config file.
+    pub fn apply(&mut self, settings: Settings) {
+        self.year = self.year.or(settings.year);
+        self.inputs = self.inputs.take().or(settings.inputs);
+        self.plugins = self.plugins.take().or(settings.plugins);
+        self.format = self.format.or(settings.format);
+        self.timeout = self.timeout.or(settings.timeout);
+        self.iterations = self.iterations.or(settings.iterations);
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("Unknown format: {}", s)),
+        }
+    }
+}
+
+fn parse_year(value: &str) -> u16 {
+    let year = value
+        .parse::<u16>()
+        .expect("Invalid year argument, expected number");
+
+    check_year(year).unwrap_or_else(|err| panic!("{}", err))
+}
+
+pub fn check_year(year: u16) -> Result<u16, String> {
+    match year {
+        2015..=2024 => Ok(year),
+        _ => Err("Year must be between 2015 and 2024 (inclusive)".to_string()),
+    }
+}
+
+fn parse_day(value: &str) -> u8 {
+    let day = value
+        .parse::<u8>()
+        .expect("Invalid day argument, expected number");
+
+    match day {
+        1..=50 => day,
+        _ => panic!("Day must be between 1 and 50 (inclusive)"),
+    }
+}
+
+fn parse_iterations(value: &str) -> u32 {
+    value
+        .parse::<u32>()
+        .map_err(|_| "Iterations must be a positive number".to_string())
+        .and_then(check_iterations)
+        .unwrap_or_else(|err| panic!("{}", err))
+}
+
+pub fn check_iterations(iterations: u32) -> Result<u32, String> {
+    if iterations > 0 {
+        Ok(iterations)
+    } else {
+        Err("Iterations must be a positive number".to_string())
+    }
+}
+
+/// Parses durations like `1s`, `250ms` or `1.5s`.
+pub fn parse_duration(value: &str) -> Duration {
+    try_parse_duration(value).unwrap_or_else(|err| panic!("{}", err))
+}
+
+pub fn try_parse_duration(value: &str) -> Result<Duration, String> {
+    let (number, unit) = value
+        .find(|c: char| c.is_ascii_alphabetic())
+        .map(|i| value.split_at(i))
+        .ok_or("Missing unit in duration, expected one of \"us\", \"ms\" or \"s\"")?;
+    let number = number
+        .parse::<f64>()
+        .map_err(|_| "Invalid duration, expected number followed by a unit")?;
+    if !number.is_finite() || number < 0.0 {
+        return Err(format!("Duration must not be negative: {}", value));
+    }
+
+    let seconds = match unit {
+        "us" => number / 1_000_000.0,
+        "ms" => number / 1_000.0,
+        "s" => number,
+        _ => return Err(format!("Unknown duration unit: {}", unit)),
+    };
+    Duration::try_from_secs_f64(seconds).map_err(|_| format!("Duration is too long: {}", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::args::{parse_duration, try_parse_duration, Args, Format};
+    use crate::config::Settings;
+    use crate::util::trace::Level;
+    use std::time::Duration;
+
+    fn parse(args: &[&str]) -> Args {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_defaults_to_run() {
+        let args = parse(&["year=2024", "day=7"]);
+        assert_eq!(args.command, "run");
+        assert_eq!(args.year(), 2024);
+        assert_eq!(args.day(), 7);
+        assert_eq!(args.variant, None);
+    }
+
+    #[test]
+    fn test_command_and_variant() {
+        let args = parse(&["compare", "day=6", "year=2015", "--variant=naive"]);
+        assert_eq!(args.command, "compare");
+        assert_eq!(args.year(), 2015);
+        assert_eq!(args.day(), 6);
+        assert_eq!(args.variant.as_deref(), Some("naive"));
+    }
+
+    #[test]
+    fn test_trace_flags() {
+        assert_eq!(parse(&["--trace"]).trace, Level::Trace);
+        assert_eq!(parse(&["--trace=info"]).trace, Level::Info);
+        assert_eq!(parse(&[]).trace, Level::Off);
+        assert!(parse(&["--explain"]).explain);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("1s"), Duration::from_secs(1));
+        assert_eq!(parse_duration("250ms"), Duration::from_millis(250));
+        assert_eq!(parse_duration("1.5s"), Duration::from_millis(1500));
+        assert_eq!(parse_duration("20us"), Duration::from_micros(20));
+
+        assert!(try_parse_duration("-1s").is_err());
+        assert!(try_parse_duration("1e300s").is_err());
+        assert!(try_parse_duration("5m").is_err());
+    }
+
+    #[test]
+    fn test_command_line_overrides_settings() {
+        let mut args = parse(&["day=7", "--format=json", "--iterations=3"]);
+        args.apply(Settings {
+            inputs: Some("./inputs".to_string()),
+            plugins: None,
+            year: Some(2024),
+            format: Some(Format::Text),
+            timeout: None,
+            iterations: Some(10),
+        });
+
+        assert_eq!(args.year(), 2024);
+        assert_eq!(args.inputs(), "./inputs");
+        assert_eq!(args.format(), Format::Json);
+        assert_eq!(args.iterations(), 3);
+        assert_eq!(args.timeout, None);
+    }
+
+    #[test]
+    fn test_settings_defaults() {
+        let args = parse(&[]);
+        assert_eq!(args.inputs(), "./src/years");
+        assert_eq!(args.format(), Format::Text);
+        assert_eq!(args.iterations(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Day must be between 1 and 50 (inclusive)")]
+    fn test_invalid_day() {
+        parse(&["year=2024", "day=51"]);
+    }
+}
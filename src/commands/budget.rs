@@ -0,0 +1,169 @@
+use crate::args::Args;
+use crate::util::runner::Runner;
+use crate::years;
+use std::cmp::Reverse;
+use std::process;
+use std::time::Duration;
+
+pub struct Measurement {
+    pub day: u8,
+    pub part: u8,
+    pub elapsed: Duration,
+    pub budget: Option<Duration>,
+}
+
+impl Measurement {
+    pub fn over_budget(&self) -> bool {
+        self.budget.is_some_and(|budget| self.elapsed > budget)
+    }
+}
+
+pub struct Report {
+    /// Every measured part, slowest first.
+    pub measurements: Vec<Measurement>,
+    pub total: Duration,
+    pub limit: Option<Duration>,
+}
+
+impl Report {
+    pub fn over_limit(&self) -> bool {
+        self.limit.is_some_and(|limit| self.total > limit)
+    }
+
+    pub fn passed(&self) -> bool {
+        !self.over_limit() && !self.measurements.iter().any(Measurement::over_budget)
+    }
+}
+
+/// Runs every day of a year and fails if any part exceeds its declared budget or
+/// the year as a whole exceeds its time limit.
+pub fn budget(args: &Args) {
+    let year = args.year();
+    let limit = args.limit.or(years::time_limit(year));
+    let report = measure(
+        &years::runners(year),
+        limit,
+        args.inputs(),
+        args.iterations(),
+    );
+
+    println!("Budget for {}:", year);
+    for measurement in &report.measurements {
+        let budget = match measurement.budget {
+            Some(budget) => format!("budget {:?}", budget),
+            None => "no budget".to_string(),
+        };
+        let status = if measurement.over_budget() {
+            "  OVER"
+        } else {
+            ""
+        };
+        println!(
+            "  Day {:2} part {}  {:>14}  ({}){}",
+            measurement.day,
+            measurement.part,
+            format!("{:?}", measurement.elapsed),
+            budget,
+            status
+        );
+    }
+
+    match report.limit {
+        Some(limit) => {
+            let status = if report.over_limit() { "  OVER" } else { "" };
+            println!("Total: {:?} (limit {:?}){}", report.total, limit, status);
+        }
+        None => println!("Total: {:?}", report.total),
+    }
+
+    if !report.passed() {
+        process::exit(1);
+    }
+}
+
+pub fn measure(
+    runners: &[Runner],
+    limit: Option<Duration>,
+    inputs: &str,
+    iterations: u32,
+) -> Report {
+    let mut measurements = Vec::new();
+
+    for runner in runners {
+        let input = runner.read_input_in(inputs);
+        for (part, variant) in runner.select(None) {
+            let (_, elapsed) = variant.bench(&input, iterations);
+            measurements.push(Measurement {
+                day: runner.day,
+                part,
+                elapsed,
+                budget: runner.budgets[part as usize - 1],
+            });
+        }
+    }
+
+    measurements.sort_by_key(|measurement| Reverse(measurement.elapsed));
+    let total = measurements
+        .iter()
+        .map(|measurement| measurement.elapsed)
+        .sum();
+
+    Report {
+        measurements,
+        total,
+        limit,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::budget::measure;
+    use crate::util::runner::{Runner, INPUTS};
+    use crate::years;
+    use std::thread;
+    use std::time::Duration;
+
+    fn runner() -> Runner {
+        Runner::new(2024, 1)
+            .part1("default", |_, _| 0.into())
+            .part2("default", |_, _| {
+                thread::sleep(Duration::from_millis(5));
+                0.into()
+            })
+            .budget(2, Duration::from_millis(1))
+    }
+
+    #[test]
+    fn test_slowest_first() {
+        let report = measure(&[runner()], None, INPUTS, 1);
+        let parts: Vec<_> = report.measurements.iter().map(|m| m.part).collect();
+        assert_eq!(parts, vec![2, 1]);
+    }
+
+    #[test]
+    fn test_over_budget() {
+        let report = measure(&[runner()], None, INPUTS, 1);
+        assert!(report.measurements[0].over_budget());
+        assert!(!report.measurements[1].over_budget());
+        assert!(!report.passed());
+    }
+
+    #[test]
+    fn test_over_limit() {
+        let report = measure(
+            &[runner().budget(2, Duration::from_secs(1))],
+            Some(Duration::ZERO),
+            INPUTS,
+            1,
+        );
+        assert!(report.over_limit());
+        assert!(!report.passed());
+    }
+
+    #[test]
+    #[ignore = "measures real timings, run with `cargo test --release -- --ignored`"]
+    fn test_2024_within_budget() {
+        let report = measure(&years::runners(2024), years::time_limit(2024), INPUTS, 3);
+        assert!(report.passed(), "2024 took {:?}", report.total);
+    }
+}
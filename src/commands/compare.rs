@@ -0,0 +1,53 @@
+use crate::args::Args;
+use crate::commands::{get_runner, load_input};
+use std::process;
+
+/// Runs every variant of every part on the same input, prints their timings
+/// relative to the fastest one and fails if any two variants disagree.
+pub fn compare(args: &Args) {
+    let runner = get_runner(args);
+    let input = load_input(&runner, args);
+    let mut mismatches = 0;
+
+    println!("Day {}:", runner.day);
+
+    for (part, variants) in runner.parts() {
+        if variants.is_empty() {
+            continue;
+        }
+
+        let results: Vec<_> = variants
+            .iter()
+            .map(|variant| variant.bench(&input, args.iterations()))
+            .collect();
+        let fastest = results.iter().map(|(_, elapsed)| *elapsed).min().unwrap();
+        let width = variants
+            .iter()
+            .map(|variant| variant.name.len())
+            .max()
+            .unwrap();
+
+        println!("Part {}:", part);
+        for (variant, (answer, elapsed)) in variants.iter().zip(&results) {
+            let ratio = elapsed.as_secs_f64() / fastest.as_secs_f64().max(f64::EPSILON);
+            println!(
+                "  {:width$}  {}  {:?} ({:.2}x)",
+                variant.name,
+                answer,
+                elapsed,
+                ratio,
+                width = width
+            );
+        }
+
+        let expected = &results[0].0;
+        if results.iter().any(|(answer, _)| answer != expected) {
+            println!("  Variants disagree on part {}", part);
+            mismatches += 1;
+        }
+    }
+
+    if mismatches > 0 {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::args::Args;
+use crate::util::history;
+use crate::util::history::{Record, HISTORY_PATH};
+use std::process;
+use std::time::Duration;
+
+/// A run this much slower than the previous run of the same variant on the same
+/// machine is flagged.
+const SLOWDOWN_THRESHOLD: f64 = 1.25;
+
+/// Slowdowns smaller than this are mostly noise and never flagged.
+const MIN_SLOWDOWN: Duration = Duration::from_millis(1);
+
+/// Shows every recorded run of a day, oldest first, flagging changed answers and
+/// slowdowns.
+pub fn history(args: &Args) {
+    let (year, day) = (args.year(), args.day());
+    let records = match history::load(HISTORY_PATH) {
+        Ok(records) => records,
+        Err(err) => {
+            eprintln!("Error: {:#}", err);
+            process::exit(1);
+        }
+    };
+
+    let records: Vec<Record> = records
+        .into_iter()
+        .filter(|record| record.year == year && record.day == day)
+        .collect();
+    if records.is_empty() {
+        println!("No runs recorded for {} day {}", year, day);
+        return;
+    }
+
+    let revision_width = column_width(&records, |record| &record.revision);
+    let variant_width = column_width(&records, |record| &record.variant);
+    let answer_width = column_width(&records, |record| &record.answer);
+
+    println!("Day {}:", day);
+
+    for part in [1, 2] {
+        let runs: Vec<&Record> = records
+            .iter()
+            .filter(|record| record.part == part)
+            .collect();
+        if runs.is_empty() {
+            continue;
+        }
+
+        println!("Part {}:", part);
+        for (record, notes) in runs.iter().zip(changes(&runs)) {
+            let line = format!(
+                "  {}  {:<revision_width$}  {:<variant_width$}  {:>answer_width$}  {:>12}  {}",
+                history::format_timestamp(record.timestamp),
+                record.revision,
+                record.variant,
+                record.answer,
+                format!("{:?}", record.elapsed),
+                notes.join(", ")
+            );
+            println!("{}", line.trim_end());
+        }
+    }
+}
+
+fn column_width(records: &[Record], field: impl Fn(&Record) -> &String) -> usize {
+    records
+        .iter()
+        .map(|record| field(record).len())
+        .max()
+        .unwrap_or(0)
+}
+
+/// What changed in every run of a part compared to the runs before it: a
+/// different answer than the previous run, or a slowdown compared to the
+/// previous run of the same variant on the same machine.
+fn changes(runs: &[&Record]) -> Vec<Vec<String>> {
+    runs.iter()
+        .enumerate()
+        .map(|(i, run)| {
+            let mut notes = Vec::new();
+            let earlier = &runs[..i];
+
+            if let Some(previous) = earlier.last() {
+                if previous.answer != run.answer {
+                    notes.push(format!("answer changed from {}", previous.answer));
+                }
+            }
+
+            let comparable = earlier
+                .iter()
+                .rev()
+                .find(|other| other.variant == run.variant && other.machine == run.machine);
+            if let Some(previous) = comparable {
+                let ratio =
+                    run.elapsed.as_secs_f64() / previous.elapsed.as_secs_f64().max(f64::EPSILON);
+                if ratio > SLOWDOWN_THRESHOLD && run.elapsed > previous.elapsed + MIN_SLOWDOWN {
+                    notes.push(format!("{:.2}x slower than {}", ratio, previous.revision));
+                }
+            }
+
+            notes
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::history::changes;
+    use crate::util::history::Record;
+    use std::time::Duration;
+
+    fn run(revision: &str, answer: &str, millis: u64, machine: &str) -> Record {
+        Record {
+            timestamp: 0,
+            revision: revision.to_string(),
+            year: 2024,
+            day: 6,
+            part: 2,
+            variant: "default".to_string(),
+            answer: answer.to_string(),
+            elapsed: Duration::from_millis(millis),
+            machine: machine.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_changes() {
+        let runs = [
+            run("a", "1309", 400, "laptop"),
+            run("b", "1309", 450, "laptop"),
+            run("c", "1309", 1, "desktop"),
+            run("c", "1309", 2, "desktop"),
+            run("d", "1310", 600, "laptop"),
+        ];
+        let runs: Vec<&Record> = runs.iter().collect();
+
+        assert_eq!(
+            changes(&runs),
+            vec![
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![
+                    "answer changed from 1309".to_string(),
+                    "1.33x slower than b".to_string()
+                ],
+            ]
+        );
+    }
+}
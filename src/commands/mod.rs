@@ -0,0 +1,59 @@
+use crate::args::Args;
+use crate::util::plugin;
+use crate::util::runner::Runner;
+use crate::years;
+use anyhow::Result;
+use std::process;
+
+mod budget;
+mod compare;
+mod history;
+mod run;
+mod scale;
+mod tui;
+
+pub use budget::budget;
+pub use compare::compare;
+pub use history::history;
+pub use run::run;
+pub use scale::scale;
+pub use tui::tui;
+
+/// Reads the input given with `--input`, or the day's own input from the inputs
+/// directory, and exits with a readable error if it can't be read or doesn't look
+/// like input for the day.
+fn load_input(runner: &Runner, args: &Args) -> String {
+    let path = args
+        .input
+        .clone()
+        .unwrap_or_else(|| runner.input_path_in(args.inputs()));
+
+    match read_input(runner, &path) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("Error: {:#}", err);
+            process::exit(1);
+        }
+    }
+}
+
+/// Reads an input file and checks that it looks like input for the day.
+fn read_input(runner: &Runner, path: &str) -> Result<String> {
+    runner
+        .read_input_from(path)
+        .and_then(|input| runner.check_input(&input).map(|_| input))
+}
+
+/// The runner for the requested day, with the variants of any plugins given with
+/// `--plugins` added to it.
+fn get_runner(args: &Args) -> Runner {
+    let mut runner = years::get_runner(args.year(), args.day());
+    if let Some(plugins) = &args.plugins {
+        if let Err(err) = plugin::install(&mut runner, plugins) {
+            eprintln!("Error: {:#}", err);
+            process::exit(1);
+        }
+    }
+
+    runner
+}
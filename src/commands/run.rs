@@ -0,0 +1,171 @@
+use crate::args::{Args, Format};
+use crate::commands::{get_runner, load_input};
+use crate::util::history;
+use crate::util::history::{Record, HISTORY_PATH};
+use crate::util::progress;
+use crate::util::progress::Progress;
+use crate::util::trace;
+use crate::util::trace::Level;
+use serde::{Serialize, Serializer};
+use std::process;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[derive(Serialize)]
+struct PartResult {
+    part: u8,
+    variant: &'static str,
+    answer: String,
+    #[serde(rename = "nanos", serialize_with = "nanos")]
+    elapsed: Duration,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    counters: Vec<(&'static str, u64)>,
+}
+
+#[derive(Serialize, Copy, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    Finished,
+    Cancelled,
+    TimedOut,
+}
+
+#[derive(Serialize)]
+struct Report {
+    year: u16,
+    day: u8,
+    status: Status,
+    parts: Vec<PartResult>,
+    #[serde(rename = "nanos", serialize_with = "nanos")]
+    elapsed: Duration,
+}
+
+pub fn run(args: &Args) {
+    let runner = get_runner(args);
+    let input = load_input(&runner, args);
+    let text = args.format() == Format::Text;
+
+    // Explaining a run also shows its high-level events
+    trace::set_level(if args.explain {
+        args.trace.max(Level::Info)
+    } else {
+        args.trace
+    });
+    trace::set_counting(args.explain);
+
+    // The first Ctrl-C asks the current part to stop, the second one gives up on it
+    let progress = Arc::new(Progress::new());
+    let handler = Arc::clone(&progress);
+    ctrlc::set_handler(move || {
+        if handler.cancel() {
+            process::exit(130);
+        }
+    })
+    .expect("Could not install the Ctrl-C handler");
+
+    if text {
+        println!("Day {}:", runner.day);
+    }
+
+    let now = Instant::now();
+    let mut status = Status::Finished;
+    let mut results = Vec::new();
+    for (part, variant) in runner.select(args.variant.as_deref()) {
+        let label = if runner.parts()[part as usize - 1].1.len() > 1 {
+            format!("Part {} ({})", part, variant.name)
+        } else {
+            format!("Part {}", part)
+        };
+
+        progress.reset();
+        let solve = || variant.run_with(&input, &progress);
+        let (answer, elapsed) = progress::show_while(&progress, &label, || match args.timeout {
+            Some(timeout) => progress::cancel_after(&progress, timeout, solve),
+            None => solve(),
+        });
+
+        if progress.is_cancelled() {
+            status = match args.timeout {
+                Some(timeout) if elapsed >= timeout => Status::TimedOut,
+                _ => Status::Cancelled,
+            };
+            if text {
+                match status {
+                    Status::TimedOut => println!("{}: timed out ({:?})", label, elapsed),
+                    _ => println!("{}: cancelled ({:?})", label, elapsed),
+                }
+            }
+            break;
+        }
+
+        let counters = if args.explain {
+            trace::take_counters()
+        } else {
+            Vec::new()
+        };
+        if text {
+            println!("{}: {} ({:?})", label, answer, elapsed);
+            for (name, value) in &counters {
+                println!("  {}: {}", name, value);
+            }
+        }
+
+        results.push(PartResult {
+            part,
+            variant: variant.name,
+            answer: answer.to_string(),
+            elapsed,
+            counters,
+        });
+    }
+
+    let report = Report {
+        year: runner.year,
+        day: runner.day,
+        status,
+        parts: results,
+        elapsed: now.elapsed(),
+    };
+    match args.format() {
+        Format::Text => println!("Time elapsed: {:?}", report.elapsed),
+        Format::Json => println!("{}", serde_json::to_string_pretty(&report).unwrap()),
+    }
+
+    // Answers for other inputs would look like regressions in the history
+    if args.input.is_none() && !report.parts.is_empty() {
+        record(&report);
+    }
+
+    match report.status {
+        Status::Finished => {}
+        Status::Cancelled => process::exit(130),
+        Status::TimedOut => process::exit(1),
+    }
+}
+
+fn record(report: &Report) {
+    let (timestamp, revision, machine) = (history::now(), history::revision(), history::machine());
+    let records: Vec<Record> = report
+        .parts
+        .iter()
+        .map(|result| Record {
+            timestamp,
+            revision: revision.clone(),
+            year: report.year,
+            day: report.day,
+            part: result.part,
+            variant: result.variant.to_string(),
+            answer: result.answer.clone(),
+            elapsed: result.elapsed,
+            machine: machine.clone(),
+        })
+        .collect();
+
+    if let Err(err) = history::append(HISTORY_PATH, &records) {
+        eprintln!("Warning: could not record this run: {:#}", err);
+    }
+}
+
+fn nanos<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u128(duration.as_nanos())
+}
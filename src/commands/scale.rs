@@ -0,0 +1,116 @@
+use crate::args::Args;
+use crate::commands::get_runner;
+use crate::util::rng::Rng;
+use crate::util::runner::Variant;
+use std::time::{Duration, Instant};
+
+const START_SIZE: usize = 1000;
+const MAX_STEPS: usize = 10;
+/// Stop growing the input once a single run takes longer than this.
+const TIME_CAP: Duration = Duration::from_secs(1);
+/// Small inputs are run repeatedly until this much time has passed, to smooth out noise.
+const MIN_SAMPLE_TIME: Duration = Duration::from_millis(20);
+
+/// Runs a day on synthetic inputs of doubling size and estimates how its running
+/// time grows by fitting `time = c * size^k` through the measurements.
+pub fn scale(args: &Args) {
+    let runner = get_runner(args);
+    let generator = runner
+        .generator
+        .unwrap_or_else(|| panic!("Day {} has no input generator", runner.day));
+
+    println!("Day {}:", runner.day);
+
+    for (part, variant) in runner.select(args.variant.as_deref()) {
+        println!("Part {} ({}):", part, variant.name);
+
+        let mut samples = Vec::new();
+        let mut size = START_SIZE;
+
+        for _ in 0..MAX_STEPS {
+            let input = generator(size, &mut Rng::new(size as u64));
+            let elapsed = measure(variant, &input);
+            println!("  {:>8}  {:?}", size, elapsed);
+
+            samples.push((size, elapsed));
+            if elapsed > TIME_CAP {
+                break;
+            }
+            size *= 2;
+        }
+
+        match growth_exponent(&samples) {
+            Some(exponent) => println!("  Estimated growth: O(n^{:.2})", exponent),
+            None => println!("  Not enough samples to estimate growth"),
+        }
+    }
+}
+
+fn measure(variant: &Variant, input: &str) -> Duration {
+    let now = Instant::now();
+    let mut runs = 0;
+
+    while runs == 0 || now.elapsed() < MIN_SAMPLE_TIME {
+        variant.run(input);
+        runs += 1;
+    }
+
+    now.elapsed() / runs
+}
+
+/// The slope of the least-squares line through the samples on a log-log scale.
+pub fn growth_exponent(samples: &[(usize, Duration)]) -> Option<f64> {
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .filter(|(_, elapsed)| !elapsed.is_zero())
+        .map(|(size, elapsed)| ((*size as f64).ln(), elapsed.as_secs_f64().ln()))
+        .collect();
+
+    if points.len() < 2 {
+        return None;
+    }
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let covariance: f64 = points
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+
+    Some(covariance / variance)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::scale::growth_exponent;
+    use std::time::Duration;
+
+    fn samples(exponent: i32) -> Vec<(usize, Duration)> {
+        [1000, 2000, 4000, 8000]
+            .into_iter()
+            .map(|size| {
+                (
+                    size,
+                    Duration::from_nanos((size as u64).pow(exponent as u32)),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_linear() {
+        assert!((growth_exponent(&samples(1)).unwrap() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_quadratic() {
+        assert!((growth_exponent(&samples(2)).unwrap() - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_too_few_samples() {
+        assert_eq!(growth_exponent(&[(1000, Duration::from_millis(1))]), None);
+    }
+}
@@ -0,0 +1,689 @@
+use crate::args::Args;
+use crate::commands::read_input;
+use crate::util::history;
+use crate::util::history::{Record, HISTORY_PATH};
+use crate::util::plugin;
+use crate::util::progress;
+use crate::util::progress::Progress;
+use crate::util::runner::panic_message;
+use crate::years;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::process;
+use std::slice;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// How long to wait for a key press before redrawing the progress of a run.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The name of the thread days are run on.
+const SOLVER_THREAD: &str = "solver";
+
+/// How many recorded runs of the selected part are shown.
+const RECENT_RUNS: usize = 5;
+
+/// Browses every day of every year, runs them and shows their answers and timings.
+pub fn tui(args: &Args) {
+    let history = match history::load(HISTORY_PATH) {
+        Ok(records) => records,
+        Err(err) => {
+            eprintln!("Error: {:#}", err);
+            process::exit(1);
+        }
+    };
+    let mut app = App::new(days(history));
+
+    let mut terminal = ratatui::init();
+
+    // Panicking solvers are shown as failed parts instead of being printed over
+    // the interface
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if thread::current().name() != Some(SOLVER_THREAD) {
+            hook(info);
+        }
+    }));
+
+    let result = event_loop(&mut terminal, &mut app, args);
+    ratatui::restore();
+
+    if let Err(err) = result {
+        eprintln!("Error: {:#}", err);
+        process::exit(1);
+    }
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App, args: &Args) -> std::io::Result<()> {
+    let mut job: Option<Job> = None;
+
+    loop {
+        terminal.draw(|frame| draw(frame, app, job.as_ref()))?;
+
+        if event::poll(POLL_INTERVAL)? {
+            if let Event::Key(key) = event::read()? {
+                match app.handle_key(key) {
+                    Action::None => {}
+                    Action::Run(parts) if job.is_none() => job = Some(Job::start(app, args, parts)),
+                    Action::Run(_) => app.message = Some("Already running".to_string()),
+                    Action::Cancel => {
+                        if let Some(job) = &job {
+                            job.progress.cancel();
+                        }
+                    }
+                    Action::Quit => {
+                        if let Some(job) = &job {
+                            job.progress.cancel();
+                        }
+                        return Ok(());
+                    }
+                }
+            }
+        }
+
+        if let Some(running) = &mut job {
+            loop {
+                match running.messages.try_recv() {
+                    Ok(message) => {
+                        running.receive(&message);
+                        app.receive(running.year, running.day, message, running.record);
+                    }
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        job = None;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// What the recorded runs say about a part.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Verification {
+    /// The part has never been run.
+    Never,
+    /// The part has been run once, so there is nothing to compare its answer to.
+    Once,
+    /// The last two runs gave the same answer.
+    Verified,
+    /// The last run gave a different answer than the run before it.
+    Changed,
+}
+
+impl Verification {
+    fn of(runs: &[Record]) -> Verification {
+        match runs {
+            [] => Verification::Never,
+            [_] => Verification::Once,
+            [.., previous, last] if previous.answer == last.answer => Verification::Verified,
+            _ => Verification::Changed,
+        }
+    }
+
+    fn symbol(&self) -> char {
+        match self {
+            Verification::Never => '·',
+            Verification::Once => '?',
+            Verification::Verified => '✓',
+            Verification::Changed => '✗',
+        }
+    }
+}
+
+/// A day in the list, with the runs recorded for each of its parts.
+struct Day {
+    year: u16,
+    day: u8,
+    /// Which parts have at least one variant.
+    implemented: [bool; 2],
+    /// Recorded runs of both parts on the day's own input, oldest first.
+    runs: [Vec<Record>; 2],
+}
+
+impl Day {
+    fn verification(&self, part: u8) -> Verification {
+        Verification::of(&self.runs[part as usize - 1])
+    }
+
+    /// The sum of the most recently recorded time of every part.
+    fn last_runtime(&self) -> Option<Duration> {
+        let last: Vec<Duration> = self
+            .runs
+            .iter()
+            .filter_map(|runs| runs.last().map(|run| run.elapsed))
+            .collect();
+        (!last.is_empty()).then(|| last.iter().sum())
+    }
+}
+
+/// Every day of every year, in order.
+fn days(history: Vec<Record>) -> Vec<Day> {
+    let mut days: Vec<Day> = years::YEARS
+        .iter()
+        .flat_map(|&year| years::runners(year))
+        .map(|runner| Day {
+            year: runner.year,
+            day: runner.day,
+            implemented: [!runner.part1.is_empty(), !runner.part2.is_empty()],
+            runs: [Vec::new(), Vec::new()],
+        })
+        .collect();
+
+    for record in history {
+        let day = days
+            .iter_mut()
+            .find(|day| (day.year, day.day) == (record.year, record.day));
+        if let (Some(day), 1..=2) = (day, record.part) {
+            day.runs[record.part as usize - 1].push(record);
+        }
+    }
+
+    days
+}
+
+/// How a part ended.
+#[derive(Debug, Clone, PartialEq)]
+enum Outcome {
+    Answer(String, Duration),
+    Cancelled(Duration),
+    Failed(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct PartRun {
+    variant: &'static str,
+    outcome: Outcome,
+}
+
+/// Sent by the thread running a day.
+#[derive(Debug, Clone, PartialEq)]
+enum Message {
+    Started(u8, &'static str),
+    Finished(u8, PartRun),
+    /// The day couldn't be run at all, e.g. because its input is missing.
+    Failed(String),
+}
+
+enum Action {
+    None,
+    Run(Vec<u8>),
+    Cancel,
+    Quit,
+}
+
+struct App {
+    days: Vec<Day>,
+    selected: usize,
+    part: u8,
+    /// The input file to run days on instead of their own input.
+    input: Option<String>,
+    /// The input path while it is being typed.
+    editing: Option<String>,
+    /// What happened the last time each day was run in this session.
+    results: HashMap<(u16, u8), [Option<PartRun>; 2]>,
+    message: Option<String>,
+    /// Looked up once when the interface starts, since finding the revision
+    /// runs git.
+    revision: String,
+    machine: String,
+}
+
+impl App {
+    fn new(days: Vec<Day>) -> App {
+        App {
+            days,
+            selected: 0,
+            part: 1,
+            input: None,
+            editing: None,
+            results: HashMap::new(),
+            message: None,
+            revision: history::revision(),
+            machine: history::machine(),
+        }
+    }
+
+    fn day(&self) -> &Day {
+        &self.days[self.selected]
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.kind != KeyEventKind::Press {
+            return Action::None;
+        }
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Action::Quit;
+        }
+
+        if let Some(path) = &mut self.editing {
+            match key.code {
+                KeyCode::Enter => {
+                    let path = path.trim().to_string();
+                    self.input = (!path.is_empty()).then_some(path);
+                    self.editing = None;
+                }
+                KeyCode::Esc => self.editing = None,
+                KeyCode::Backspace => {
+                    path.pop();
+                }
+                KeyCode::Char(c) => path.push(c),
+                _ => {}
+            }
+            return Action::None;
+        }
+
+        self.message = None;
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(self.days.len() - 1)
+            }
+            KeyCode::Left | KeyCode::Right | KeyCode::Tab | KeyCode::BackTab => {
+                self.part = 3 - self.part
+            }
+            KeyCode::Char('1') => self.part = 1,
+            KeyCode::Char('2') => self.part = 2,
+            KeyCode::Enter => return Action::Run(vec![1, 2]),
+            KeyCode::Char(' ') => return Action::Run(vec![self.part]),
+            KeyCode::Char('i') => self.editing = Some(self.input.clone().unwrap_or_default()),
+            KeyCode::Char('c') | KeyCode::Esc => return Action::Cancel,
+            KeyCode::Char('q') => return Action::Quit,
+            _ => {}
+        }
+
+        Action::None
+    }
+
+    /// Takes in a message about a running day, recording finished parts in the
+    /// history if they ran on the day's own input.
+    fn receive(&mut self, year: u16, day: u8, message: Message, record: bool) {
+        match message {
+            Message::Started(part, _) => {
+                self.results.entry((year, day)).or_default()[part as usize - 1] = None;
+            }
+            Message::Finished(part, run) => {
+                if let (true, Outcome::Answer(answer, elapsed)) = (record, &run.outcome) {
+                    let record = Record {
+                        timestamp: history::now(),
+                        revision: self.revision.clone(),
+                        year,
+                        day,
+                        part,
+                        variant: run.variant.to_string(),
+                        answer: answer.clone(),
+                        elapsed: *elapsed,
+                        machine: self.machine.clone(),
+                    };
+                    if let Err(err) = history::append(HISTORY_PATH, slice::from_ref(&record)) {
+                        self.message = Some(format!("Could not record this run: {:#}", err));
+                    }
+                    if let Some(day) = self
+                        .days
+                        .iter_mut()
+                        .find(|other| (other.year, other.day) == (year, day))
+                    {
+                        day.runs[part as usize - 1].push(record);
+                    }
+                }
+                self.results.entry((year, day)).or_default()[part as usize - 1] = Some(run);
+            }
+            Message::Failed(err) => self.message = Some(err),
+        }
+    }
+}
+
+/// A day running on a background thread, so that the interface stays responsive
+/// and the run can be cancelled.
+struct Job {
+    year: u16,
+    day: u8,
+    progress: Arc<Progress>,
+    messages: Receiver<Message>,
+    /// Whether finished parts go into the history, which only holds runs on the
+    /// day's own input.
+    record: bool,
+    /// The part that is running, with its label.
+    current: Option<(u8, String)>,
+}
+
+impl Job {
+    fn start(app: &App, args: &Args, parts: Vec<u8>) -> Job {
+        let (year, day) = (app.day().year, app.day().day);
+        let progress = Arc::new(Progress::new());
+        let (sender, messages) = mpsc::channel();
+
+        let input = app.input.clone().or(args.input.clone());
+        let record = input.is_none();
+        let (inputs, plugins) = (args.inputs().to_string(), args.plugins.clone());
+        let (variant, timeout) = (args.variant.clone(), args.timeout);
+        let worker = Arc::clone(&progress);
+
+        let solver = thread::Builder::new().name(SOLVER_THREAD.to_string());
+        let spawned = solver.spawn(move || {
+            let progress = worker;
+            let fail = |err: anyhow::Error| sender.send(Message::Failed(format!("{:#}", err)));
+
+            let mut runner = years::get_runner(year, day);
+            if let Some(plugins) = plugins {
+                if let Err(err) = plugin::install(&mut runner, &plugins) {
+                    return fail(err);
+                }
+            }
+            let path = input.unwrap_or_else(|| runner.input_path_in(&inputs));
+            let input = match read_input(&runner, &path) {
+                Ok(input) => input,
+                Err(err) => return fail(err),
+            };
+
+            for (part, variant) in runner.select(variant.as_deref()) {
+                if !parts.contains(&part) || progress.is_cancelled() {
+                    continue;
+                }
+
+                progress.reset();
+                sender.send(Message::Started(part, variant.name))?;
+                let solve = || variant.run_with(&input, &progress);
+                let result = panic::catch_unwind(AssertUnwindSafe(|| match timeout {
+                    Some(timeout) => progress::cancel_after(&progress, timeout, solve),
+                    None => solve(),
+                }));
+
+                let outcome = match result {
+                    Ok((_, elapsed)) if progress.is_cancelled() => Outcome::Cancelled(elapsed),
+                    Ok((answer, elapsed)) => Outcome::Answer(answer.to_string(), elapsed),
+                    Err(payload) => Outcome::Failed(panic_message(payload)),
+                };
+                let run = PartRun {
+                    variant: variant.name,
+                    outcome,
+                };
+                sender.send(Message::Finished(part, run))?;
+            }
+
+            Ok(())
+        });
+        spawned.expect("Could not start the solver thread");
+
+        Job {
+            year,
+            day,
+            progress,
+            messages,
+            record,
+            current: None,
+        }
+    }
+
+    fn receive(&mut self, message: &Message) {
+        self.current = match message {
+            Message::Started(part, variant) => {
+                Some((*part, format!("Part {} ({})", part, variant)))
+            }
+            _ => None,
+        };
+    }
+}
+
+fn draw(frame: &mut Frame, app: &App, job: Option<&Job>) {
+    let [main, footer] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+    let [list, details] =
+        Layout::horizontal([Constraint::Length(36), Constraint::Min(0)]).areas(main);
+
+    draw_days(frame, app, list);
+    draw_details(frame, app, job, details);
+
+    let footer_text = match (&app.editing, &app.message) {
+        (Some(path), _) => format!(
+            "Input file: {}_  (enter to use, empty for the day's own input, esc to keep)",
+            path
+        ),
+        (None, Some(message)) => message.clone(),
+        (None, None) => {
+            "↑↓ day  ←→ part  enter run  space run part  i input file  c cancel  q quit".to_string()
+        }
+    };
+    frame.render_widget(Paragraph::new(footer_text), footer);
+}
+
+fn draw_days(frame: &mut Frame, app: &App, area: Rect) {
+    let rows = app.days.iter().map(|day| {
+        let parts: String = [1, 2]
+            .iter()
+            .filter(|&&part| day.implemented[part as usize - 1])
+            .map(|&part| day.verification(part).symbol())
+            .collect();
+        let runtime = day
+            .last_runtime()
+            .map_or(String::new(), |runtime| format!("{:.1?}", runtime));
+
+        Row::new(vec![
+            day.year.to_string(),
+            format!("{:>2}", day.day),
+            parts,
+            format!("{:>10}", runtime),
+        ])
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(4),
+            Constraint::Length(3),
+            Constraint::Length(5),
+            Constraint::Length(10),
+        ],
+    )
+    .header(
+        Row::new(vec!["Year", "Day", "Parts", "  Last run"])
+            .style(Style::new().add_modifier(Modifier::BOLD)),
+    )
+    .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+    .block(Block::bordered().title("Days"));
+
+    let mut state = TableState::new().with_selected(Some(app.selected));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn draw_details(frame: &mut Frame, app: &App, job: Option<&Job>, area: Rect) {
+    let day = app.day();
+    let running = job.filter(|job| (job.year, job.day) == (day.year, day.day));
+    let results = app.results.get(&(day.year, day.day));
+
+    let mut lines = vec![
+        Line::from(format!(
+            "Input: {}",
+            app.input.as_deref().unwrap_or("the day's own input")
+        )),
+        Line::from(""),
+    ];
+
+    for part in [1, 2] {
+        let marker = if part == app.part { "▶" } else { " " };
+        let mut header = Line::from(format!("{} Part {}", marker, part));
+        if part == app.part {
+            header = header.style(Style::new().add_modifier(Modifier::BOLD));
+        }
+        lines.push(header);
+
+        let current = running.and_then(|job| job.current.as_ref());
+        let result = results.and_then(|results| results[part as usize - 1].as_ref());
+        let status = match (current, result) {
+            _ if !day.implemented[part as usize - 1] => "not implemented".to_string(),
+            (Some((current, label)), _) if *current == part => {
+                format!("running {}", progress_of(running, label))
+            }
+            (_, Some(run)) => match &run.outcome {
+                Outcome::Answer(answer, elapsed) => {
+                    format!("{}  ({:?}, {})", answer, elapsed, run.variant)
+                }
+                Outcome::Cancelled(elapsed) => format!("cancelled after {:?}", elapsed),
+                Outcome::Failed(err) => format!("failed: {}", err),
+            },
+            _ => "not run yet".to_string(),
+        };
+        lines.push(Line::from(format!("    {}", status)));
+        lines.push(Line::from(""));
+    }
+
+    let runs = &day.runs[app.part as usize - 1];
+    lines.push(
+        Line::from(format!("Recent runs of part {}:", app.part))
+            .style(Style::new().add_modifier(Modifier::BOLD)),
+    );
+    if runs.is_empty() {
+        lines.push(Line::from("    none recorded"));
+    }
+    for run in runs.iter().rev().take(RECENT_RUNS) {
+        lines.push(Line::from(format!(
+            "    {}  {}  {}  {}  {:?}",
+            history::format_timestamp(run.timestamp),
+            run.revision,
+            run.variant,
+            run.answer,
+            run.elapsed
+        )));
+    }
+
+    let title = format!("{} day {}", day.year, day.day);
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(title)),
+        area,
+    );
+}
+
+fn progress_of(job: Option<&Job>, label: &str) -> String {
+    job.and_then(|job| job.progress.status(label))
+        .unwrap_or_else(|| label.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::tui::{draw, App, Day, Message, Outcome, PartRun, Verification};
+    use crate::util::history::Record;
+    use ratatui::backend::TestBackend;
+    use ratatui::crossterm::event::{KeyCode, KeyEvent};
+    use ratatui::Terminal;
+    use std::time::Duration;
+
+    fn run(answer: &str) -> Record {
+        Record {
+            timestamp: 0,
+            revision: "c1bb852".to_string(),
+            year: 2024,
+            day: 1,
+            part: 1,
+            variant: "default".to_string(),
+            answer: answer.to_string(),
+            elapsed: Duration::from_millis(2),
+            machine: "box".to_string(),
+        }
+    }
+
+    fn app() -> App {
+        let day = |day| Day {
+            year: 2024,
+            day,
+            implemented: [true, day != 25],
+            runs: [Vec::new(), Vec::new()],
+        };
+        App::new(vec![day(1), day(2), day(25)])
+    }
+
+    fn press(app: &mut App, codes: &[KeyCode]) {
+        for &code in codes {
+            app.handle_key(KeyEvent::from(code));
+        }
+    }
+
+    #[test]
+    fn test_verification() {
+        assert_eq!(Verification::of(&[]), Verification::Never);
+        assert_eq!(Verification::of(&[run("1")]), Verification::Once);
+        assert_eq!(
+            Verification::of(&[run("2"), run("1"), run("1")]),
+            Verification::Verified
+        );
+        assert_eq!(
+            Verification::of(&[run("1"), run("2")]),
+            Verification::Changed
+        );
+    }
+
+    #[test]
+    fn test_navigation() {
+        let mut app = app();
+        press(
+            &mut app,
+            &[KeyCode::Up, KeyCode::Down, KeyCode::Down, KeyCode::Down],
+        );
+        assert_eq!(app.day().day, 25);
+
+        press(&mut app, &[KeyCode::Tab]);
+        assert_eq!(app.part, 2);
+        press(&mut app, &[KeyCode::Char('1')]);
+        assert_eq!(app.part, 1);
+    }
+
+    #[test]
+    fn test_edit_input() {
+        let mut app = app();
+        press(&mut app, &[KeyCode::Char('i')]);
+        for c in "example.txx".chars() {
+            press(&mut app, &[KeyCode::Char(c)]);
+        }
+        press(&mut app, &[KeyCode::Backspace, KeyCode::Char('t')]);
+        // Keys go to the path while it is being typed
+        assert_eq!(app.selected, 0);
+        press(&mut app, &[KeyCode::Enter]);
+        assert_eq!(app.input.as_deref(), Some("example.txt"));
+
+        press(
+            &mut app,
+            &[KeyCode::Char('i'), KeyCode::Backspace, KeyCode::Esc],
+        );
+        assert_eq!(app.input.as_deref(), Some("example.txt"));
+        press(&mut app, &[KeyCode::Char('i')]);
+        for _ in 0.."example.txt".len() {
+            press(&mut app, &[KeyCode::Backspace]);
+        }
+        press(&mut app, &[KeyCode::Enter]);
+        assert_eq!(app.input, None);
+    }
+
+    #[test]
+    fn test_draws_results() {
+        let mut app = app();
+        let run = PartRun {
+            variant: "default",
+            outcome: Outcome::Answer("3246517".to_string(), Duration::from_millis(2)),
+        };
+        app.receive(2024, 1, Message::Finished(1, run), false);
+        app.receive(2024, 1, Message::Failed("No such file".to_string()), false);
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        terminal.draw(|frame| draw(frame, &app, None)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+
+        assert!(screen.contains("2024 day 1"));
+        assert!(screen.contains("3246517  (2ms, default)"));
+        assert!(screen.contains("not run yet"));
+        assert!(screen.contains("No such file"));
+    }
+}
@@ -0,0 +1,197 @@
+use crate::args::{check_iterations, check_year, try_parse_duration, Format};
+use anyhow::{anyhow, Context, Result};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// Optional project configuration, read from the directory the runner is started in.
+pub const CONFIG_PATH: &str = "./.aoc.toml";
+
+/// Defaults for command-line arguments. Every key is optional and command-line
+/// flags always win.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    /// Directory with a `y{year}/day{day}.txt` file per day.
+    pub inputs: Option<String>,
+    /// Directory with `cdylib` plugins to load variants from.
+    pub plugins: Option<String>,
+    #[serde(deserialize_with = "year")]
+    pub year: Option<u16>,
+    pub format: Option<Format>,
+    /// How long a part may run before it is cancelled, e.g. `"10s"`.
+    #[serde(deserialize_with = "duration")]
+    pub timeout: Option<Duration>,
+    /// How many times benchmarks run each part, keeping the fastest run.
+    #[serde(deserialize_with = "iterations")]
+    pub iterations: Option<u32>,
+}
+
+impl Settings {
+    /// Takes every setting from `self` that is set, and the rest from `other`.
+    fn or(self, other: Settings) -> Settings {
+        Settings {
+            inputs: self.inputs.or(other.inputs),
+            plugins: self.plugins.or(other.plugins),
+            year: self.year.or(other.year),
+            format: self.format.or(other.format),
+            timeout: self.timeout.or(other.timeout),
+            iterations: self.iterations.or(other.iterations),
+        }
+    }
+}
+
+/// The top-level settings of the file plus named profiles that override them, e.g.
+///
+/// ```toml
+/// year = 2024
+/// profile = "dev"
+///
+/// [profiles.ci]
+/// timeout = "5s"
+/// iterations = 10
+/// ```
+#[derive(Debug, Default)]
+pub struct Config {
+    /// The profile used when none is given with `--profile`.
+    pub profile: Option<String>,
+    pub defaults: Settings,
+    pub profiles: BTreeMap<String, Settings>,
+}
+
+impl Config {
+    pub fn parse(text: &str) -> Result<Config> {
+        let mut table: toml::Table = text.parse()?;
+        let profile = table
+            .remove("profile")
+            .map(|profile| profile.try_into::<String>())
+            .transpose()?;
+        let profiles = table
+            .remove("profiles")
+            .map(|profiles| profiles.try_into::<BTreeMap<String, Settings>>())
+            .transpose()?
+            .unwrap_or_default();
+        let defaults = toml::Value::Table(table).try_into::<Settings>()?;
+
+        Ok(Config {
+            profile,
+            defaults,
+            profiles,
+        })
+    }
+
+    /// The settings for the given profile, or for the configured one if none is given.
+    pub fn settings(&self, profile: Option<&str>) -> Result<Settings> {
+        match profile.or(self.profile.as_deref()) {
+            Some(name) => {
+                let profile = self
+                    .profiles
+                    .get(name)
+                    .ok_or_else(|| anyhow!("Unknown profile: {}", name))?;
+                Ok(profile.clone().or(self.defaults.clone()))
+            }
+            None => Ok(self.defaults.clone()),
+        }
+    }
+}
+
+/// Reads the settings from the config file, which doesn't have to exist.
+pub fn load(path: &str, profile: Option<&str>) -> Result<Settings> {
+    let config = if Path::new(path).exists() {
+        let text = fs::read_to_string(path).with_context(|| format!("Could not read {}", path))?;
+        Config::parse(&text).with_context(|| format!("Could not parse {}", path))?
+    } else {
+        Config::default()
+    };
+
+    config.settings(profile)
+}
+
+// The same checks as the command-line flags, so that a bad config file fails
+// to load instead of panicking later on.
+
+fn year<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u16>, D::Error> {
+    check_year(u16::deserialize(deserializer)?)
+        .map(Some)
+        .map_err(D::Error::custom)
+}
+
+fn iterations<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u32>, D::Error> {
+    check_iterations(u32::deserialize(deserializer)?)
+        .map(Some)
+        .map_err(D::Error::custom)
+}
+
+fn duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    try_parse_duration(&value)
+        .map(Some)
+        .map_err(D::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::args::Format;
+    use crate::config::{Config, Settings};
+    use std::time::Duration;
+
+    const CONFIG: &str = r#"
+year = 2024
+inputs = "./inputs"
+profile = "dev"
+
+[profiles.dev]
+format = "text"
+
+[profiles.ci]
+format = "json"
+timeout = "1.5s"
+iterations = 5
+"#;
+
+    #[test]
+    fn test_profile_overrides_defaults() {
+        let config = Config::parse(CONFIG).unwrap();
+
+        assert_eq!(
+            config.settings(Some("ci")).unwrap(),
+            Settings {
+                inputs: Some("./inputs".to_string()),
+                plugins: None,
+                year: Some(2024),
+                format: Some(Format::Json),
+                timeout: Some(Duration::from_millis(1500)),
+                iterations: Some(5),
+            }
+        );
+        assert_eq!(config.settings(None).unwrap().format, Some(Format::Text));
+    }
+
+    #[test]
+    fn test_unknown_profile() {
+        let config = Config::parse(CONFIG).unwrap();
+        let error = config.settings(Some("release")).unwrap_err();
+        assert_eq!(error.to_string(), "Unknown profile: release");
+    }
+
+    #[test]
+    fn test_rejects_unknown_keys() {
+        assert!(Config::parse("yaer = 2024").is_err());
+        assert!(Config::parse("[profiles.ci]\ntimeout = \"5 minutes\"").is_err());
+        assert!(Config::parse("").is_ok());
+    }
+
+    #[test]
+    fn test_rejects_values_the_flags_reject() {
+        assert!(Config::parse("timeout = \"-1s\"").is_err());
+        let error = Config::parse("year = 1999").unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Year must be between 2015 and 2024 (inclusive)"));
+        assert!(Config::parse("[profiles.ci]\niterations = 0").is_err());
+        assert!(Config::parse("year = 2015\niterations = 1").is_ok());
+    }
+}
@@ -0,0 +1,348 @@
+//! Dense sets of small numbers and of grid positions, stored as one bit each,
+//! for visited sets in hot loops where hashing would dominate.
+
+use crate::util::grid::Position;
+
+/// A set of the numbers `0..capacity`, one bit each.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    words: Vec<u64>,
+    capacity: usize,
+}
+
+impl BitSet {
+    pub fn new(capacity: usize) -> BitSet {
+        BitSet {
+            words: vec![0; capacity.div_ceil(64)],
+            capacity,
+        }
+    }
+
+    /// How many numbers the set can hold, which is one more than the largest.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Adds the number, returning whether it wasn't in the set yet. Panics if
+    /// the number is outside of the capacity.
+    pub fn insert(&mut self, value: usize) -> bool {
+        let (word, bit) = self.locate(value);
+        let added = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        added
+    }
+
+    /// Removes the number, returning whether it was in the set.
+    pub fn remove(&mut self, value: usize) -> bool {
+        let (word, bit) = self.locate(value);
+        let removed = self.words[word] & bit != 0;
+        self.words[word] &= !bit;
+        removed
+    }
+
+    /// Whether the number is in the set, which is never the case for numbers
+    /// outside of the capacity.
+    pub fn contains(&self, value: usize) -> bool {
+        value < self.capacity && self.words[value / 64] & (1 << (value % 64)) != 0
+    }
+
+    /// How many numbers are in the set.
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// Removes every number, keeping the capacity.
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// Adds every number of the other set, which must have the same capacity.
+    pub fn union_with(&mut self, other: &BitSet) {
+        self.combine(other, |a, b| a | b);
+    }
+
+    /// Removes every number that isn't in the other set, which must have the
+    /// same capacity.
+    pub fn intersect_with(&mut self, other: &BitSet) {
+        self.combine(other, |a, b| a & b);
+    }
+
+    /// Removes every number of the other set, which must have the same capacity.
+    pub fn difference_with(&mut self, other: &BitSet) {
+        self.combine(other, |a, b| a & !b);
+    }
+
+    pub fn union(&self, other: &BitSet) -> BitSet {
+        let mut union = self.clone();
+        union.union_with(other);
+        union
+    }
+
+    pub fn intersection(&self, other: &BitSet) -> BitSet {
+        let mut intersection = self.clone();
+        intersection.intersect_with(other);
+        intersection
+    }
+
+    /// Every number in the set, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut rest = word;
+            std::iter::from_fn(move || {
+                if rest == 0 {
+                    return None;
+                }
+                let bit = rest.trailing_zeros() as usize;
+                rest &= rest - 1;
+                Some(i * 64 + bit)
+            })
+        })
+    }
+
+    fn locate(&self, value: usize) -> (usize, u64) {
+        assert!(
+            value < self.capacity,
+            "{} is outside of a set of {}",
+            value,
+            self.capacity
+        );
+        (value / 64, 1 << (value % 64))
+    }
+
+    fn combine(&mut self, other: &BitSet, combine: impl Fn(u64, u64) -> u64) {
+        assert_eq!(
+            self.capacity, other.capacity,
+            "Expected sets of the same capacity"
+        );
+        for (word, &other) in self.words.iter_mut().zip(&other.words) {
+            *word = combine(*word, other);
+        }
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    /// A set just large enough for every number.
+    fn from_iter<I: IntoIterator<Item = usize>>(values: I) -> BitSet {
+        let values: Vec<usize> = values.into_iter().collect();
+        let mut set = BitSet::new(values.iter().max().map_or(0, |max| max + 1));
+        for value in values {
+            set.insert(value);
+        }
+        set
+    }
+}
+
+/// A set of positions in a grid, optionally with a small state for every
+/// position, like the direction something was facing there. Every combination
+/// of position and state takes one bit.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    bits: BitSet,
+    width: usize,
+    height: usize,
+    states: usize,
+}
+
+impl BitGrid {
+    /// A set of positions without states.
+    pub fn new(width: usize, height: usize) -> BitGrid {
+        BitGrid::with_states(width, height, 1)
+    }
+
+    /// A set of positions that each have `states` possible states.
+    pub fn with_states(width: usize, height: usize, states: usize) -> BitGrid {
+        BitGrid {
+            bits: BitSet::new(width * height * states),
+            width,
+            height,
+            states,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Adds the position, returning whether it wasn't in the set yet.
+    pub fn insert(&mut self, position: Position) -> bool {
+        self.insert_state(position, 0)
+    }
+
+    pub fn contains(&self, position: Position) -> bool {
+        self.contains_state(position, 0)
+    }
+
+    /// Adds the position in the given state, returning whether it wasn't in the
+    /// set in that state yet. Panics if either is out of bounds.
+    pub fn insert_state(&mut self, position: Position, state: usize) -> bool {
+        let index = self.index(position, state);
+        self.bits.insert(index)
+    }
+
+    /// Whether the position is in the set in the given state, which is never the
+    /// case for positions outside of the grid.
+    pub fn contains_state(&self, position: Position, state: usize) -> bool {
+        position.0 < self.width
+            && position.1 < self.height
+            && state < self.states
+            && self.bits.contains(self.index(position, state))
+    }
+
+    /// How many combinations of position and state are in the set.
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    /// Removes everything, keeping the size.
+    pub fn clear(&mut self) {
+        self.bits.clear();
+    }
+
+    /// Adds everything in the other set, which must have the same size.
+    pub fn union_with(&mut self, other: &BitGrid) {
+        self.bits.union_with(&other.bits);
+    }
+
+    /// Removes everything that isn't in the other set, which must have the
+    /// same size.
+    pub fn intersect_with(&mut self, other: &BitGrid) {
+        self.bits.intersect_with(&other.bits);
+    }
+
+    /// Every combination of position and state in the set, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, usize)> + '_ {
+        self.bits.iter().map(|index| {
+            let (cell, state) = (index / self.states, index % self.states);
+            ((cell % self.width, cell / self.width), state)
+        })
+    }
+
+    /// Every position that is in the set in any state, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Position> + '_ {
+        let mut last = None;
+        self.iter()
+            .map(|(position, _)| position)
+            .filter(move |&position| last.replace(position) != Some(position))
+    }
+
+    fn index(&self, (x, y): Position, state: usize) -> usize {
+        assert!(
+            x < self.width && y < self.height && state < self.states,
+            "{:?} in state {} is outside of the grid",
+            (x, y),
+            state
+        );
+        (y * self.width + x) * self.states + state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::bitset::{BitGrid, BitSet};
+    use crate::util::check::agree;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_bit_set() {
+        let mut set = BitSet::new(130);
+        assert!(set.insert(0) && set.insert(64) && set.insert(129));
+        assert!(!set.insert(64));
+        assert!(set.contains(129) && !set.contains(128) && !set.contains(500));
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [0, 64, 129]);
+
+        let other: BitSet = [3, 64].into_iter().collect();
+        assert_eq!(other.capacity(), 65);
+        let mut other_wide = BitSet::new(130);
+        other_wide.union_with(&BitSet::from_iter([3, 64, 129]));
+        assert_eq!(
+            set.intersection(&other_wide).iter().collect::<Vec<_>>(),
+            [64, 129]
+        );
+        assert_eq!(set.union(&other_wide).len(), 4);
+        set.difference_with(&other_wide);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [0]);
+
+        assert!(set.remove(0) && !set.remove(0));
+        assert!(set.is_empty());
+        set.insert(5);
+        set.clear();
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "outside of a set")]
+    fn test_bit_set_bounds() {
+        BitSet::new(64).insert(64);
+    }
+
+    #[test]
+    fn test_bit_grid() {
+        let mut grid = BitGrid::with_states(3, 2, 4);
+        assert!(grid.insert_state((2, 1), 3));
+        assert!(grid.insert_state((2, 1), 0));
+        assert!(grid.insert_state((0, 1), 1));
+        assert!(!grid.insert_state((2, 1), 3));
+        assert!(grid.contains((2, 1)) && !grid.contains((0, 1)));
+        assert!(!grid.contains_state((3, 0), 0) && !grid.contains_state((0, 0), 4));
+        assert_eq!(grid.len(), 3);
+        assert_eq!(
+            grid.iter().collect::<Vec<_>>(),
+            [((0, 1), 1), ((2, 1), 0), ((2, 1), 3)]
+        );
+        assert_eq!(grid.positions().collect::<Vec<_>>(), [(0, 1), (2, 1)]);
+
+        grid.clear();
+        assert!(grid.is_empty());
+    }
+
+    #[test]
+    fn test_against_btree_set() {
+        agree(
+            200,
+            |rng| {
+                (0..rng.range(0..40))
+                    .map(|_| (rng.range(0..2), rng.range(0..200)))
+                    .collect::<Vec<_>>()
+            },
+            |operations| {
+                let mut set = BTreeSet::new();
+                for &(operation, value) in operations {
+                    if operation == 0 {
+                        set.insert(value);
+                    } else {
+                        set.remove(&value);
+                    }
+                }
+                set.into_iter().collect::<Vec<_>>()
+            },
+            |operations| {
+                let mut set = BitSet::new(200);
+                for &(operation, value) in operations {
+                    if operation == 0 {
+                        set.insert(value);
+                    } else {
+                        set.remove(value);
+                    }
+                }
+                assert_eq!(set.len(), set.iter().count());
+                set.iter().collect()
+            },
+        );
+    }
+}
@@ -0,0 +1,250 @@
+use crate::util::rng::Rng;
+use crate::util::runner::panic_message;
+use std::fmt::Debug;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Give up shrinking after this many successful steps and report what we have.
+const MAX_SHRINK_STEPS: usize = 1000;
+
+/// Values that can be made simpler, so that a failing property test can be
+/// reduced to a minimal input.
+///
+/// Shrinking knows nothing about the constraints of a puzzle, so properties
+/// should normalize their input (deduplicate, clamp...) before using it.
+pub trait Shrink: Clone + Debug {
+    /// Candidates that are strictly simpler than `self`, most aggressive first.
+    fn shrink(&self) -> Vec<Self>;
+}
+
+macro_rules! impl_shrink_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl Shrink for $t {
+                fn shrink(&self) -> Vec<Self> {
+                    let mut candidates = Vec::new();
+                    if *self > 0 {
+                        candidates.push(0);
+                    }
+                    if *self > 2 {
+                        candidates.push(*self / 2);
+                    }
+                    if *self > 1 {
+                        candidates.push(*self - 1);
+                    }
+                    candidates
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_shrink_signed {
+    ($($t:ty),*) => {
+        $(
+            impl Shrink for $t {
+                fn shrink(&self) -> Vec<Self> {
+                    let mut candidates = Vec::new();
+                    if *self != 0 {
+                        candidates.push(0);
+                    }
+                    if let Some(positive) = self.checked_neg().filter(|_| *self < 0) {
+                        candidates.push(positive);
+                    }
+                    if self.unsigned_abs() > 2 {
+                        candidates.push(*self / 2);
+                    }
+                    if self.unsigned_abs() > 1 {
+                        candidates.push(*self - self.signum());
+                    }
+                    candidates
+                }
+            }
+        )*
+    };
+}
+
+impl_shrink_unsigned!(u8, u16, u32, u64, usize);
+impl_shrink_signed!(i8, i16, i32, i64, isize);
+
+impl<T: Shrink> Shrink for Vec<T> {
+    fn shrink(&self) -> Vec<Self> {
+        let mut candidates = Vec::new();
+
+        if !self.is_empty() {
+            candidates.push(Vec::new());
+        }
+        if self.len() > 2 {
+            candidates.push(self[..self.len() / 2].to_vec());
+            candidates.push(self[self.len() / 2..].to_vec());
+        }
+        if self.len() > 1 {
+            for i in 0..self.len() {
+                let mut candidate = self.clone();
+                candidate.remove(i);
+                candidates.push(candidate);
+            }
+        }
+        for (i, item) in self.iter().enumerate() {
+            for smaller in item.shrink() {
+                let mut candidate = self.clone();
+                candidate[i] = smaller;
+                candidates.push(candidate);
+            }
+        }
+
+        candidates
+    }
+}
+
+impl<A: Shrink, B: Shrink> Shrink for (A, B) {
+    fn shrink(&self) -> Vec<Self> {
+        let (a, b) = self;
+        let mut candidates: Vec<Self> = a.shrink().into_iter().map(|a| (a, b.clone())).collect();
+        candidates.extend(b.shrink().into_iter().map(|b| (a.clone(), b)));
+        candidates
+    }
+}
+
+impl<A: Shrink, B: Shrink, C: Shrink> Shrink for (A, B, C) {
+    fn shrink(&self) -> Vec<Self> {
+        let (a, b, c) = self;
+        let mut candidates: Vec<Self> = a
+            .shrink()
+            .into_iter()
+            .map(|a| (a, b.clone(), c.clone()))
+            .collect();
+        candidates.extend(b.shrink().into_iter().map(|b| (a.clone(), b, c.clone())));
+        candidates.extend(c.shrink().into_iter().map(|c| (a.clone(), b.clone(), c)));
+        candidates
+    }
+}
+
+/// Checks a property on `cases` generated values, each generated from its own
+/// seed. On failure the value is shrunk to a minimal failing one before panicking.
+/// A property that panics counts as failing.
+pub fn forall<T: Shrink>(
+    cases: u64,
+    generate: impl Fn(&mut Rng) -> T,
+    property: impl Fn(&T) -> Result<(), String>,
+) {
+    for seed in 0..cases {
+        let value = generate(&mut Rng::new(seed));
+
+        if let Err(message) = test(&property, &value) {
+            let (minimal, message) = minimize(value.clone(), message, &property);
+            panic!(
+                "Property failed for seed {}\n  input:    {:?}\n  shrunk:   {:?}\n  failure:  {}",
+                seed, value, minimal, message
+            );
+        }
+    }
+}
+
+/// Checks that an optimized implementation agrees with a brute-force reference
+/// on every generated value.
+pub fn agree<T: Shrink, R: PartialEq + Debug>(
+    cases: u64,
+    generate: impl Fn(&mut Rng) -> T,
+    reference: impl Fn(&T) -> R,
+    optimized: impl Fn(&T) -> R,
+) {
+    forall(cases, generate, |value| {
+        let expected = reference(value);
+        let actual = optimized(value);
+        if expected == actual {
+            Ok(())
+        } else {
+            Err(format!("expected {:?} but got {:?}", expected, actual))
+        }
+    })
+}
+
+fn test<T>(property: &impl Fn(&T) -> Result<(), String>, value: &T) -> Result<(), String> {
+    panic::catch_unwind(AssertUnwindSafe(|| property(value)))
+        .unwrap_or_else(|panic| Err(format!("panicked: {}", panic_message(panic))))
+}
+
+fn minimize<T: Shrink>(
+    mut value: T,
+    mut message: String,
+    property: &impl Fn(&T) -> Result<(), String>,
+) -> (T, String) {
+    'steps: for _ in 0..MAX_SHRINK_STEPS {
+        for candidate in value.shrink() {
+            if let Err(failure) = test(property, &candidate) {
+                value = candidate;
+                message = failure;
+                continue 'steps;
+            }
+        }
+        break;
+    }
+
+    (value, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::check::{agree, forall, minimize, Shrink};
+
+    #[test]
+    fn test_shrink_integers() {
+        assert_eq!(10u8.shrink(), vec![0, 5, 9]);
+        assert_eq!((-10i32).shrink(), vec![0, 10, -5, -9]);
+        assert!(0usize.shrink().is_empty());
+    }
+
+    #[test]
+    fn test_minimize_to_smallest_failing_value() {
+        let property = |values: &Vec<u8>| {
+            let sum: u32 = values.iter().map(|&x| x as u32).sum();
+            if sum < 100 {
+                Ok(())
+            } else {
+                Err(format!("sum is {}", sum))
+            }
+        };
+
+        let (minimal, message) = minimize(vec![80, 3, 60, 7, 90], String::new(), &property);
+        assert!(minimal.len() <= 2);
+        assert_eq!(message, "sum is 100");
+    }
+
+    #[test]
+    fn test_minimize_panics() {
+        let property = |values: &Vec<usize>| {
+            let _ = values[3];
+            Ok(())
+        };
+
+        let (minimal, message) = minimize(vec![5, 6], String::new(), &property);
+        assert_eq!(minimal, Vec::<usize>::new());
+        assert!(message.starts_with("panicked: index out of bounds"));
+    }
+
+    #[test]
+    fn test_forall_passes() {
+        forall(
+            100,
+            |rng| rng.range(0..10),
+            |value| {
+                if *value < 10 {
+                    Ok(())
+                } else {
+                    Err("out of range".to_string())
+                }
+            },
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "shrunk:   (0, 1)")]
+    fn test_agree_reports_shrunk_input() {
+        agree(
+            100,
+            |rng| (rng.range(0..1000), rng.range(0..1000)),
+            |(a, b)| a + b,
+            |(a, b)| if *b > 0 { a + b + 1 } else { a + b },
+        );
+    }
+}
@@ -0,0 +1,216 @@
+//! A multiset that counts how often each item occurs, for the puzzles that
+//! would otherwise keep a `HashMap<T, usize>` of counts by hand.
+
+use std::collections::hash_map;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::{Add, AddAssign, Index, Sub, SubAssign};
+
+/// How often each item occurs. Items that don't occur have a count of zero and
+/// are never stored, so two counters with the same counts are always equal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter<T: Eq + Hash> {
+    counts: HashMap<T, usize>,
+}
+
+impl<T: Eq + Hash> Counter<T> {
+    pub fn new() -> Counter<T> {
+        Counter {
+            counts: HashMap::new(),
+        }
+    }
+
+    /// How often the item occurs, which is zero for items that were never added.
+    pub fn get(&self, item: &T) -> usize {
+        self.counts.get(item).copied().unwrap_or(0)
+    }
+
+    /// Counts the item once more.
+    pub fn insert(&mut self, item: T) {
+        self.add(item, 1);
+    }
+
+    /// Counts the item `count` more times.
+    pub fn add(&mut self, item: T, count: usize) {
+        if count > 0 {
+            *self.counts.entry(item).or_default() += count;
+        }
+    }
+
+    /// Counts the item `count` fewer times, stopping at zero. Returns how many
+    /// times the item was actually taken away.
+    pub fn subtract(&mut self, item: &T, count: usize) -> usize {
+        let Some(current) = self.counts.get_mut(item) else {
+            return 0;
+        };
+        if *current > count {
+            *current -= count;
+            count
+        } else {
+            self.counts.remove(item).unwrap()
+        }
+    }
+
+    /// Adds every count of the other counter to this one.
+    pub fn merge(&mut self, other: Counter<T>) {
+        for (item, count) in other.counts {
+            self.add(item, count);
+        }
+    }
+
+    /// How many different items occur.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// How many items there are, counting every occurrence.
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Every item that occurs along with its count, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, usize)> {
+        self.counts.iter().map(|(item, &count)| (item, count))
+    }
+
+    /// Every item that occurs, in no particular order.
+    pub fn items(&self) -> impl Iterator<Item = &T> {
+        self.counts.keys()
+    }
+
+    /// Every item that occurs along with its count, the most common first and
+    /// equally common items in increasing order.
+    pub fn most_common(&self) -> Vec<(&T, usize)>
+    where
+        T: Ord,
+    {
+        let mut counts: Vec<_> = self.iter().collect();
+        counts.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        counts
+    }
+}
+
+impl<T: Eq + Hash> Default for Counter<T> {
+    fn default() -> Counter<T> {
+        Counter::new()
+    }
+}
+
+impl<T: Eq + Hash> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(items: I) -> Counter<T> {
+        let mut counter = Counter::new();
+        counter.extend(items);
+        counter
+    }
+}
+
+impl<T: Eq + Hash> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        for item in items {
+            self.insert(item);
+        }
+    }
+}
+
+impl<T: Eq + Hash> IntoIterator for Counter<T> {
+    type Item = (T, usize);
+    type IntoIter = hash_map::IntoIter<T, usize>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.counts.into_iter()
+    }
+}
+
+/// The count of an item, so `counter[&item]` is zero for items that were never
+/// added.
+impl<T: Eq + Hash> Index<&T> for Counter<T> {
+    type Output = usize;
+
+    fn index(&self, item: &T) -> &usize {
+        self.counts.get(item).unwrap_or(&0)
+    }
+}
+
+impl<T: Eq + Hash> AddAssign for Counter<T> {
+    fn add_assign(&mut self, other: Counter<T>) {
+        self.merge(other);
+    }
+}
+
+/// Adds the counts of both counters.
+impl<T: Eq + Hash> Add for Counter<T> {
+    type Output = Counter<T>;
+
+    fn add(mut self, other: Counter<T>) -> Counter<T> {
+        self += other;
+        self
+    }
+}
+
+impl<T: Eq + Hash> SubAssign for Counter<T> {
+    fn sub_assign(&mut self, other: Counter<T>) {
+        for (item, count) in &other.counts {
+            self.subtract(item, *count);
+        }
+    }
+}
+
+/// Subtracts the counts of the other counter, dropping items whose count would
+/// go below one.
+impl<T: Eq + Hash> Sub for Counter<T> {
+    type Output = Counter<T>;
+
+    fn sub(mut self, other: Counter<T>) -> Counter<T> {
+        self -= other;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::counter::Counter;
+
+    #[test]
+    fn test_counting() {
+        let mut counter: Counter<char> = "abracadabra".chars().collect();
+        assert_eq!(
+            (counter.get(&'a'), counter[&'b'], counter.get(&'z')),
+            (5, 2, 0)
+        );
+        assert_eq!((counter.len(), counter.total()), (5, 11));
+        assert_eq!(
+            counter.most_common(),
+            [(&'a', 5), (&'b', 2), (&'r', 2), (&'c', 1), (&'d', 1)]
+        );
+
+        counter.add('z', 3);
+        counter.add('y', 0);
+        assert_eq!(counter.subtract(&'z', 2), 2);
+        assert_eq!(counter.subtract(&'z', 2), 1);
+        assert_eq!(counter.subtract(&'y', 1), 0);
+        assert_eq!(counter.len(), 5);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a: Counter<u8> = [1, 1, 2, 3].into_iter().collect();
+        let b: Counter<u8> = [1, 2, 2, 4].into_iter().collect();
+
+        let sum = a.clone() + b.clone();
+        assert_eq!((sum[&1], sum[&2], sum[&3], sum[&4]), (3, 3, 1, 1));
+
+        // Counts never go below zero, and items that reach zero are dropped
+        let difference = a.clone() - b.clone();
+        assert_eq!(difference, [1, 3].into_iter().collect());
+        assert!((b.clone() - b.clone()).is_empty());
+
+        let mut merged = Counter::new();
+        merged.merge(a);
+        merged += b;
+        assert_eq!(merged, sum);
+    }
+}
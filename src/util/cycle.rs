@@ -0,0 +1,202 @@
+//! Cycle detection for simulations that step from one state to the next, so
+//! that the state after an enormous number of steps can be found by skipping
+//! whole cycles.
+//!
+//! Every step function must be deterministic: the same state always has to
+//! lead to the same next state.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Where a sequence of states starts repeating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// How many steps it takes to reach the first state that repeats.
+    pub start: usize,
+    /// How many steps it takes for that state to come around again.
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The first step that has the same state as the given one.
+    pub fn equivalent(self, steps: usize) -> usize {
+        if steps < self.start {
+            steps
+        } else {
+            self.start + (steps - self.start) % self.length
+        }
+    }
+}
+
+/// Finds the cycle in the states starting at `start` with Brent's algorithm,
+/// or `None` if `step` ends the sequence first. Only two states are kept at a
+/// time, but the sequence is walked about three times.
+pub fn find_cycle<S: Clone + Eq>(start: S, mut step: impl FnMut(&S) -> Option<S>) -> Option<Cycle> {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start)?;
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare)?;
+        length += 1;
+    }
+
+    let start = cycle_start(start, length, |state| {
+        step(state).expect("a repeating sequence to go on")
+    });
+    Some(Cycle { start, length })
+}
+
+/// Finds the cycle in the states starting at `start` by remembering every state
+/// seen, or `None` if `step` ends the sequence first. The sequence is only
+/// walked once.
+pub fn find_cycle_hashed<S: Clone + Eq + Hash>(
+    start: S,
+    mut step: impl FnMut(&S) -> Option<S>,
+) -> Option<Cycle> {
+    let mut seen = HashMap::new();
+    let mut state = start;
+    let mut steps = 0;
+    loop {
+        match seen.entry(state.clone()) {
+            Entry::Occupied(first) => {
+                return Some(Cycle {
+                    start: *first.get(),
+                    length: steps - first.get(),
+                })
+            }
+            Entry::Vacant(first) => {
+                first.insert(steps);
+            }
+        }
+        state = step(&state)?;
+        steps += 1;
+    }
+}
+
+/// The state after taking the given number of steps from `start`, skipping
+/// whole cycles as soon as one is found.
+pub fn after<S: Clone + Eq>(start: S, steps: usize, mut step: impl FnMut(&S) -> S) -> S {
+    if steps == 0 {
+        return start;
+    }
+
+    // Brent's algorithm, where the hare is always `taken` steps from the start
+    let mut power = 1;
+    let mut length = 1;
+    let mut taken = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if taken == steps {
+            return hare;
+        }
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+        taken += 1;
+    }
+
+    let cycle = Cycle {
+        start: cycle_start(start.clone(), length, &mut step),
+        length,
+    };
+    (0..cycle.equivalent(steps)).fold(start, |state, _| step(&state))
+}
+
+/// How many steps it takes to reach a cycle of the given length, found by
+/// walking two states that are `length` steps apart until they meet.
+fn cycle_start<S: Clone + Eq>(start: S, length: usize, mut step: impl FnMut(&S) -> S) -> usize {
+    let mut tortoise = start.clone();
+    let mut hare = (0..length).fold(start, |state, _| step(&state));
+    let mut steps = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        steps += 1;
+    }
+
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::check::agree;
+    use crate::util::cycle::{after, find_cycle, find_cycle_hashed, Cycle};
+
+    // Walks 0, 1, ..., 6 and then goes round 3, 4, 5, 6 forever
+    fn step(&state: &u32) -> u32 {
+        if state == 6 {
+            3
+        } else {
+            state + 1
+        }
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let expected = Some(Cycle {
+            start: 3,
+            length: 4,
+        });
+        assert_eq!(find_cycle(0, |state| Some(step(state))), expected);
+        assert_eq!(find_cycle_hashed(0, |state| Some(step(state))), expected);
+
+        let fixed_point = Some(Cycle {
+            start: 0,
+            length: 1,
+        });
+        assert_eq!(find_cycle(5, |&state| Some(state)), fixed_point);
+        assert_eq!(find_cycle_hashed(5, |&state| Some(state)), fixed_point);
+
+        let ending = |&state: &u32| (state < 10).then_some(state + 1);
+        assert_eq!(find_cycle(0, ending), None);
+        assert_eq!(find_cycle_hashed(0, ending), None);
+    }
+
+    #[test]
+    fn test_after() {
+        assert_eq!(after(0, 0, step), 0);
+        assert_eq!(after(0, 2, step), 2);
+        assert_eq!(after(0, 7, step), 3);
+        assert_eq!(after(0, 1_000_000_000_001, step), 5);
+
+        let cycle = find_cycle(0, |state| Some(step(state))).unwrap();
+        assert_eq!(cycle.equivalent(2), 2);
+        assert_eq!(cycle.equivalent(11), 3);
+    }
+
+    #[test]
+    fn test_against_naive_iteration() {
+        // x² + c modulo a small number always ends up in a cycle
+        agree(
+            300,
+            |rng| {
+                let start = (rng.range(0..50), rng.range(0..50));
+                (start, rng.range(0..60), rng.range(0..200))
+            },
+            |&((start, c), modulus, steps)| {
+                let step = |x: &usize| (x * x + c) % (modulus + 2);
+                let naive = (0..steps).fold(start, |x, _| step(&x));
+                (naive, find_cycle(start, |x| Some(step(x))))
+            },
+            |&((start, c), modulus, steps)| {
+                let step = |x: &usize| (x * x + c) % (modulus + 2);
+                (
+                    after(start, steps, step),
+                    find_cycle_hashed(start, |x| Some(step(x))),
+                )
+            },
+        );
+    }
+}
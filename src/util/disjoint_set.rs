@@ -0,0 +1,281 @@
+//! Union-find over the numbers `0..len`, for puzzles that merge things into
+//! groups one connection at a time, along with a helper that groups the cells
+//! of a grid.
+
+use crate::util::grid::{Grid, Position};
+
+/// Splits the numbers `0..len` into disjoint sets, starting with every number
+/// on its own. Merging uses union by rank and lookups compress paths, so every
+/// operation takes almost constant time.
+#[derive(Debug, Clone)]
+pub struct DisjointSet {
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+    sizes: Vec<usize>,
+    count: usize,
+}
+
+impl DisjointSet {
+    pub fn new(len: usize) -> DisjointSet {
+        DisjointSet {
+            parents: (0..len).collect(),
+            ranks: vec![0; len],
+            sizes: vec![1; len],
+            count: len,
+        }
+    }
+
+    /// Adds a new number in a set of its own and returns it.
+    pub fn push(&mut self) -> usize {
+        let element = self.parents.len();
+        self.parents.push(element);
+        self.ranks.push(0);
+        self.sizes.push(1);
+        self.count += 1;
+        element
+    }
+
+    /// How many numbers there are in all sets together.
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// How many disjoint sets there are.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The representative of the set the number is in, which is the same for
+    /// every number in that set until the set is merged with another.
+    pub fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        // Point everything on the way straight at the root
+        let mut current = element;
+        while self.parents[current] != root {
+            let next = self.parents[current];
+            self.parents[current] = root;
+            current = next;
+        }
+
+        root
+    }
+
+    /// Merges the sets of both numbers, returning whether they were apart.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        // Hang the shallower tree under the deeper one
+        let (root, child) = if self.ranks[a] < self.ranks[b] {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        self.parents[child] = root;
+        self.sizes[root] += self.sizes[child];
+        if self.ranks[root] == self.ranks[child] {
+            self.ranks[root] += 1;
+        }
+        self.count -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// How many numbers are in the same set as this one, counting itself.
+    pub fn size(&mut self, element: usize) -> usize {
+        let root = self.find(element);
+        self.sizes[root]
+    }
+
+    /// Every set, each sorted and ordered by its smallest number.
+    pub fn sets(&mut self) -> Vec<Vec<usize>> {
+        let mut indices = vec![usize::MAX; self.len()];
+        let mut sets: Vec<Vec<usize>> = Vec::with_capacity(self.count);
+        for element in 0..self.len() {
+            let root = self.find(element);
+            if indices[root] == usize::MAX {
+                indices[root] = sets.len();
+                sets.push(Vec::with_capacity(self.sizes[root]));
+            }
+            sets[indices[root]].push(element);
+        }
+
+        sets
+    }
+}
+
+/// The cells of a grid in disjoint sets, see [`grid_components`].
+#[derive(Debug, Clone)]
+pub struct GridComponents {
+    set: DisjointSet,
+    width: usize,
+    height: usize,
+}
+
+/// Groups the cells of a grid into components of orthogonal neighbours for
+/// which `equivalent` holds. `equivalent` must be symmetric, and is called
+/// once for every pair of neighbours.
+pub fn grid_components<T>(
+    grid: &Grid<T>,
+    mut equivalent: impl FnMut(&T, &T) -> bool,
+) -> GridComponents {
+    let width = grid.width();
+    let mut set = DisjointSet::new(width * grid.height());
+    for ((x, y), cell) in grid.iter() {
+        let index = y * width + x;
+        if x + 1 < width && equivalent(cell, &grid[(x + 1, y)]) {
+            set.union(index, index + 1);
+        }
+        if y + 1 < grid.height() && equivalent(cell, &grid[(x, y + 1)]) {
+            set.union(index, index + width);
+        }
+    }
+
+    GridComponents {
+        set,
+        width,
+        height: grid.height(),
+    }
+}
+
+impl GridComponents {
+    /// How many components there are.
+    pub fn count(&self) -> usize {
+        self.set.count()
+    }
+
+    pub fn connected(&mut self, a: Position, b: Position) -> bool {
+        self.set.connected(self.index(a), self.index(b))
+    }
+
+    /// How many cells are in the same component as this one.
+    pub fn size(&mut self, position: Position) -> usize {
+        let index = self.index(position);
+        self.set.size(index)
+    }
+
+    /// Every cell in the same component as this one, row by row.
+    pub fn component(&mut self, position: Position) -> Vec<Position> {
+        let root = self.set.find(self.index(position));
+        let width = self.width;
+        (0..self.set.len())
+            .filter(|&index| self.set.find(index) == root)
+            .map(|index| (index % width, index / width))
+            .collect()
+    }
+
+    /// Every component, each listed row by row and ordered by its first cell.
+    pub fn components(&mut self) -> Vec<Vec<Position>> {
+        let width = self.width;
+        self.set
+            .sets()
+            .into_iter()
+            .map(|set| {
+                set.into_iter()
+                    .map(|index| (index % width, index / width))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn index(&self, (x, y): Position) -> usize {
+        assert!(
+            x < self.width && y < self.height,
+            "{},{} is outside of a {}x{} grid",
+            x,
+            y,
+            self.width,
+            self.height
+        );
+        y * self.width + x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::check::agree;
+    use crate::util::disjoint_set::{grid_components, DisjointSet};
+    use crate::util::grid::Grid;
+    use crate::util::regions::Regions;
+
+    #[test]
+    fn test_union_find() {
+        let mut set = DisjointSet::new(6);
+        assert!(set.union(0, 1));
+        assert!(set.union(2, 3));
+        assert!(set.union(1, 3));
+        assert!(!set.union(0, 2));
+        assert_eq!(set.count(), 3);
+        assert!(set.connected(0, 3) && !set.connected(0, 4));
+        assert_eq!((set.size(2), set.size(5)), (4, 1));
+
+        let new = set.push();
+        set.union(new, 4);
+        assert_eq!(set.sets(), [vec![0, 1, 2, 3], vec![4, 6], vec![5]]);
+    }
+
+    #[test]
+    fn test_grid_components() {
+        let grid = Grid::parse("AAB\nBAB\nBBB").unwrap();
+        let mut components = grid_components(&grid, |a, b| a == b);
+        assert_eq!(components.count(), 2);
+        assert_eq!(components.size((2, 2)), 6);
+        assert!(components.connected((2, 0), (0, 1)));
+        assert_eq!(components.component((1, 1)), [(0, 0), (1, 0), (1, 1)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "0,3 is outside of a 3x3 grid")]
+    fn test_grid_components_bounds() {
+        let grid = Grid::parse("AAB\nBAB\nBBB").unwrap();
+        grid_components(&grid, |a, b| a == b).size((0, 3));
+    }
+
+    #[test]
+    fn test_against_regions() {
+        agree(
+            200,
+            |rng| {
+                let width = rng.range(1..7);
+                let cells = (0..width * rng.range(1..7))
+                    .map(|_| rng.range(0..3))
+                    .collect::<Vec<_>>();
+                (width, cells)
+            },
+            |(width, cells)| {
+                let grid = to_grid(*width, cells)?;
+                let regions = Regions::new(&grid);
+                Some(
+                    regions
+                        .iter()
+                        .map(|region| regions.cells(region.label).collect())
+                        .collect::<Vec<Vec<_>>>(),
+                )
+            },
+            |(width, cells)| {
+                let grid = to_grid(*width, cells)?;
+                Some(grid_components(&grid, |a, b| a == b).components())
+            },
+        );
+    }
+
+    /// Cuts the cells into rows, dropping an incomplete last row.
+    fn to_grid(width: usize, cells: &[usize]) -> Option<Grid<usize>> {
+        let width = width.max(1);
+        let rows = cells.chunks_exact(width).map(<[usize]>::to_vec).collect();
+        Grid::from_rows(rows).ok()
+    }
+}
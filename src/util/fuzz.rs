@@ -0,0 +1,170 @@
+use crate::util::rng::Rng;
+use crate::util::runner::panic_message;
+use crate::util::runner::Parser;
+use std::panic;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// Fragments that tend to break parsers: separators, signs, huge numbers and
+/// characters no puzzle input contains.
+const NOISE: &[&str] = &[
+    "",
+    " ",
+    "\n",
+    "\n\n",
+    ",",
+    ":",
+    "|",
+    "-",
+    "x",
+    "0",
+    "9",
+    "99999999999999999999999",
+    "#",
+    "^",
+    "é",
+];
+
+/// Cuts a real input down to the first few lines and columns of its first few
+/// blocks, which keeps its structure but makes it cheap to parse many times.
+pub fn sample(input: &str) -> String {
+    input
+        .split("\n\n")
+        .take(10)
+        .map(|block| {
+            block
+                .lines()
+                .take(10)
+                .map(|line| line.chars().take(100).collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Derives a probably malformed input from a valid one by applying a few random
+/// edits: deleting, duplicating or replacing a range, inserting noise, truncating
+/// or swapping lines.
+pub fn mutate(input: &str, rng: &mut Rng) -> String {
+    let mut chars: Vec<char> = input.chars().collect();
+
+    for _ in 0..rng.range(1..4) {
+        if chars.is_empty() {
+            chars.extend(rng.choose(NOISE).chars());
+            continue;
+        }
+
+        let start = rng.range(0..chars.len());
+        let end = (start + rng.range(1..10)).min(chars.len());
+
+        match rng.range(0..6) {
+            0 => {
+                chars.drain(start..end);
+            }
+            1 => {
+                let copy = chars[start..end].to_vec();
+                chars.splice(start..start, copy);
+            }
+            2 => {
+                chars.splice(start..end, rng.choose(NOISE).chars());
+            }
+            3 => {
+                chars.splice(start..start, rng.choose(NOISE).chars());
+            }
+            4 => chars.truncate(start),
+            _ => {
+                let line: String = chars.iter().collect();
+                let mut lines: Vec<&str> = line.split('\n').collect();
+                let (a, b) = (rng.range(0..lines.len()), rng.range(0..lines.len()));
+                lines.swap(a, b);
+                chars = lines.join("\n").chars().collect();
+            }
+        }
+    }
+
+    chars.into_iter().collect()
+}
+
+/// Builds a random input out of the characters of a valid one and some noise.
+pub fn random(sample: &str, rng: &mut Rng) -> String {
+    let alphabet: Vec<char> = sample.chars().take(1000).collect();
+    let length = rng.range(0..50);
+
+    (0..length)
+        .map(|_| match rng.range(0..4) {
+            0 => rng.choose(NOISE).to_string(),
+            _ if alphabet.is_empty() => String::new(),
+            _ => rng.choose(&alphabet).to_string(),
+        })
+        .collect()
+}
+
+/// Runs the parser on its own thread, failing if it panics or doesn't return in
+/// time. Whether it accepts or rejects the input doesn't matter.
+pub fn survives(parser: Parser, input: &str, timeout: Duration) -> Result<(), String> {
+    let (sender, receiver) = mpsc::channel();
+    let input = input.to_string();
+
+    thread::spawn(move || {
+        let result = panic::catch_unwind(|| parser(&input));
+        let _ = sender.send(result.map(drop).map_err(panic_message));
+    });
+
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => result.map_err(|message| format!("panicked: {}", message)),
+        Err(_) => Err(format!("did not return within {:?}", timeout)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::fuzz::{mutate, sample, survives};
+    use crate::util::rng::Rng;
+    use std::time::Duration;
+
+    #[test]
+    fn test_mutate_changes_input() {
+        let input = "47|53\n97|13\n\n75,47,61";
+        let changed = (0..20)
+            .filter(|&seed| mutate(input, &mut Rng::new(seed)) != input)
+            .count();
+        assert!(changed > 15);
+    }
+
+    #[test]
+    fn test_sample_keeps_structure() {
+        let input = format!("{}\n\n{}", ["1|2"; 50].join("\n"), "1".repeat(500));
+        assert_eq!(
+            sample(&input),
+            format!("{}\n\n{}", ["1|2"; 10].join("\n"), "1".repeat(100))
+        );
+    }
+
+    #[test]
+    fn test_survives() {
+        let timeout = Duration::from_secs(1);
+        let parse = |input: &str| {
+            input.parse::<u8>()?;
+            Ok(())
+        };
+        assert!(survives(parse, "x", timeout).is_ok());
+
+        let unwrap = |input: &str| {
+            input.parse::<u8>().unwrap();
+            Ok(())
+        };
+        let error = survives(unwrap, "x", timeout);
+        assert!(error.unwrap_err().starts_with("panicked: "));
+
+        let error = survives(
+            |_| loop {
+                std::thread::sleep(Duration::from_millis(10));
+            },
+            "",
+            Duration::from_millis(50),
+        );
+        assert_eq!(error.unwrap_err(), "did not return within 50ms");
+    }
+}
@@ -0,0 +1,441 @@
+use crate::util::grid::Position;
+use anyhow::{anyhow, Result};
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+/// A point or a vector in the plane, with `y` growing downwards like in puzzle
+/// input.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+/// An integer type that can be a coordinate.
+pub trait Coordinate: Copy + Ord + Add<Output = Self> + Sub<Output = Self> {
+    const ZERO: Self;
+
+    /// The distance between two values, which never overflows for unsigned types.
+    fn distance(self, other: Self) -> Self;
+
+    fn to_index(self) -> Option<usize>;
+}
+
+/// A coordinate that can step in every direction, which rules out unsigned
+/// types since they can't go north or west of zero.
+pub trait SignedCoordinate: Coordinate + Neg<Output = Self> {
+    /// Converts a unit offset to the coordinate type, see [`Direction4::offset`].
+    fn unit(offset: i8) -> Self;
+}
+
+macro_rules! impl_coordinate {
+    ($($t:ty),*) => {
+        $(
+            impl Coordinate for $t {
+                const ZERO: Self = 0;
+
+                fn distance(self, other: Self) -> Self {
+                    self.max(other) - self.min(other)
+                }
+
+                fn to_index(self) -> Option<usize> {
+                    usize::try_from(self).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_coordinate!(i32, i64, isize, usize);
+
+macro_rules! impl_signed_coordinate {
+    ($($t:ty),*) => {
+        $(
+            impl SignedCoordinate for $t {
+                fn unit(offset: i8) -> Self {
+                    Self::from(offset)
+                }
+            }
+        )*
+    };
+}
+
+impl_signed_coordinate!(i32, i64, isize);
+
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Point<T> {
+        Point { x, y }
+    }
+}
+
+impl<T: Coordinate> Point<T> {
+    pub const ORIGIN: Point<T> = Point::new(T::ZERO, T::ZERO);
+
+    /// The number of orthogonal steps between two points.
+    pub fn manhattan(self, other: Point<T>) -> T {
+        self.x.distance(other.x) + self.y.distance(other.y)
+    }
+
+    /// The number of steps between two points when diagonal steps are allowed.
+    pub fn chebyshev(self, other: Point<T>) -> T {
+        self.x.distance(other.x).max(self.y.distance(other.y))
+    }
+
+    /// The smallest coordinates of both points, e.g. the top left corner of a
+    /// bounding box.
+    pub fn min(self, other: Point<T>) -> Point<T> {
+        Point::new(self.x.min(other.x), self.y.min(other.y))
+    }
+
+    /// The largest coordinates of both points.
+    pub fn max(self, other: Point<T>) -> Point<T> {
+        Point::new(self.x.max(other.x), self.y.max(other.y))
+    }
+
+    /// The grid position of the point, unless either coordinate is negative.
+    pub fn position(self) -> Option<Position> {
+        Some((self.x.to_index()?, self.y.to_index()?))
+    }
+}
+
+impl<T: TryFrom<usize>> Point<T> {
+    /// The point for a grid position, unless it doesn't fit the coordinate type.
+    pub fn from_position((x, y): Position) -> Option<Point<T>> {
+        Some(Point::new(T::try_from(x).ok()?, T::try_from(y).ok()?))
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, rhs: Point<T>) -> Point<T> {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Point<T>;
+
+    fn sub(self, rhs: Point<T>) -> Point<T> {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point<T> {
+    fn add_assign(&mut self, rhs: Point<T>) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point<T> {
+    fn sub_assign(&mut self, rhs: Point<T>) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point<T> {
+    type Output = Point<T>;
+
+    fn neg(self) -> Point<T> {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+/// Scales a vector.
+impl<T: Copy + Mul<Output = T>> Mul<T> for Point<T> {
+    type Output = Point<T>;
+
+    fn mul(self, rhs: T) -> Point<T> {
+        Point::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T: SignedCoordinate> Add<Direction4> for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, rhs: Direction4) -> Point<T> {
+        self + rhs.offset()
+    }
+}
+
+impl<T: SignedCoordinate> Add<Direction8> for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, rhs: Direction8) -> Point<T> {
+        self + rhs.offset()
+    }
+}
+
+impl<T: SignedCoordinate> AddAssign<Direction4> for Point<T> {
+    fn add_assign(&mut self, rhs: Direction4) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: SignedCoordinate> AddAssign<Direction8> for Point<T> {
+    fn add_assign(&mut self, rhs: Direction8) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+/// One of the four orthogonal directions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Direction4 {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction4 {
+    /// Every direction, clockwise from north.
+    pub const ALL: [Direction4; 4] = [
+        Direction4::North,
+        Direction4::East,
+        Direction4::South,
+        Direction4::West,
+    ];
+
+    pub fn turn_right(self) -> Direction4 {
+        Direction4::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Direction4 {
+        Direction4::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Direction4 {
+        Direction4::ALL[(self as usize + 2) % 4]
+    }
+
+    /// The vector of a single step in this direction.
+    pub fn offset<T: SignedCoordinate>(self) -> Point<T> {
+        let (x, y) = match self {
+            Direction4::North => (0, -1),
+            Direction4::East => (1, 0),
+            Direction4::South => (0, 1),
+            Direction4::West => (-1, 0),
+        };
+        Point::new(T::unit(x), T::unit(y))
+    }
+
+    /// Parses one of `^>v<`.
+    pub fn from_arrow(c: char) -> Option<Direction4> {
+        match c {
+            '^' => Some(Direction4::North),
+            '>' => Some(Direction4::East),
+            'v' => Some(Direction4::South),
+            '<' => Some(Direction4::West),
+            _ => None,
+        }
+    }
+
+    /// Parses one of `NESW`.
+    pub fn from_compass(c: char) -> Option<Direction4> {
+        match c {
+            'N' => Some(Direction4::North),
+            'E' => Some(Direction4::East),
+            'S' => Some(Direction4::South),
+            'W' => Some(Direction4::West),
+            _ => None,
+        }
+    }
+
+    /// Parses one of `UDLR`, for up, down, left and right.
+    pub fn from_letter(c: char) -> Option<Direction4> {
+        match c {
+            'U' => Some(Direction4::North),
+            'R' => Some(Direction4::East),
+            'D' => Some(Direction4::South),
+            'L' => Some(Direction4::West),
+            _ => None,
+        }
+    }
+}
+
+/// Accepts any of `^>v<`, `NESW` and `UDLR`.
+impl TryFrom<char> for Direction4 {
+    type Error = anyhow::Error;
+
+    fn try_from(c: char) -> Result<Direction4> {
+        Direction4::from_arrow(c)
+            .or_else(|| Direction4::from_compass(c))
+            .or_else(|| Direction4::from_letter(c))
+            .ok_or_else(|| anyhow!("expected a direction but found {:?}", c))
+    }
+}
+
+impl FromStr for Direction4 {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Direction4> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Direction4::try_from(c),
+            _ => Err(anyhow!("expected a direction but found {:?}", s)),
+        }
+    }
+}
+
+/// One of the four orthogonal and four diagonal directions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Direction8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction8 {
+    /// Every direction, clockwise from north.
+    pub const ALL: [Direction8; 8] = [
+        Direction8::North,
+        Direction8::NorthEast,
+        Direction8::East,
+        Direction8::SouthEast,
+        Direction8::South,
+        Direction8::SouthWest,
+        Direction8::West,
+        Direction8::NorthWest,
+    ];
+
+    /// Turns 45 degrees clockwise.
+    pub fn turn_right(self) -> Direction8 {
+        Direction8::ALL[(self as usize + 1) % 8]
+    }
+
+    /// Turns 45 degrees counterclockwise.
+    pub fn turn_left(self) -> Direction8 {
+        Direction8::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn reverse(self) -> Direction8 {
+        Direction8::ALL[(self as usize + 4) % 8]
+    }
+
+    /// The vector of a single step in this direction.
+    pub fn offset<T: SignedCoordinate>(self) -> Point<T> {
+        let (x, y) = match self {
+            Direction8::North => (0, -1),
+            Direction8::NorthEast => (1, -1),
+            Direction8::East => (1, 0),
+            Direction8::SouthEast => (1, 1),
+            Direction8::South => (0, 1),
+            Direction8::SouthWest => (-1, 1),
+            Direction8::West => (-1, 0),
+            Direction8::NorthWest => (-1, -1),
+        };
+        Point::new(T::unit(x), T::unit(y))
+    }
+}
+
+impl From<Direction4> for Direction8 {
+    fn from(direction: Direction4) -> Direction8 {
+        Direction8::ALL[direction as usize * 2]
+    }
+}
+
+/// Accepts compass names like `N` and `NE`, or a single `^>v<` or `UDLR`.
+impl FromStr for Direction8 {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Direction8> {
+        match s {
+            "NE" => Ok(Direction8::NorthEast),
+            "SE" => Ok(Direction8::SouthEast),
+            "SW" => Ok(Direction8::SouthWest),
+            "NW" => Ok(Direction8::NorthWest),
+            _ => s.parse::<Direction4>().map(Direction8::from),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::geometry::{Direction4, Direction8, Point};
+
+    #[test]
+    fn test_arithmetic() {
+        let (a, b) = (Point::new(3, -2), Point::new(-1, 4));
+        assert_eq!(a + b, Point::new(2, 2));
+        assert_eq!(a - b, Point::new(4, -6));
+        assert_eq!(-a, Point::new(-3, 2));
+        assert_eq!(a * 3, Point::new(9, -6));
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+        assert_eq!(a.to_string(), "3,-2");
+    }
+
+    #[test]
+    fn test_distances() {
+        let (a, b) = (Point::new(3, -2), Point::new(-1, 4));
+        assert_eq!(a.manhattan(b), 10);
+        assert_eq!(a.chebyshev(b), 6);
+        assert_eq!(Point::new(2usize, 7).manhattan(Point::new(5, 1)), 9);
+
+        assert_eq!(a.min(b), Point::new(-1, -2));
+        assert_eq!(a.max(b), Point::new(3, 4));
+    }
+
+    #[test]
+    fn test_positions() {
+        assert_eq!(Point::new(3i32, 2).position(), Some((3, 2)));
+        assert_eq!(Point::new(-1i64, 2).position(), None);
+        assert_eq!(Point::<i32>::from_position((3, 2)), Some(Point::new(3, 2)));
+        assert_eq!(Point::<i32>::from_position((usize::MAX, 0)), None);
+    }
+
+    #[test]
+    fn test_direction4() {
+        let north = Direction4::North;
+        assert_eq!(north.turn_right(), Direction4::East);
+        assert_eq!(north.turn_left(), Direction4::West);
+        assert_eq!(north.reverse(), Direction4::South);
+        assert_eq!(Direction4::West.turn_right(), Direction4::North);
+
+        assert_eq!(Point::new(2, 2) + north, Point::new(2, 1));
+        assert_eq!(Point::new(2i64, 2) + Direction4::East, Point::new(3, 2));
+
+        let parsed: Vec<Direction4> = "^>v<NESWURDL"
+            .chars()
+            .map(|c| Direction4::try_from(c).unwrap())
+            .collect();
+        assert_eq!(parsed[..4], Direction4::ALL);
+        assert_eq!(parsed[4..8], Direction4::ALL);
+        assert_eq!(parsed[8..], Direction4::ALL);
+        assert_eq!(Direction4::from_arrow('N'), None);
+        assert!("x".parse::<Direction4>().is_err());
+    }
+
+    #[test]
+    fn test_direction8() {
+        assert_eq!(Direction8::North.turn_right(), Direction8::NorthEast);
+        assert_eq!(Direction8::North.turn_left(), Direction8::NorthWest);
+        assert_eq!(Direction8::SouthWest.reverse(), Direction8::NorthEast);
+        assert_eq!(Direction8::from(Direction4::West), Direction8::West);
+
+        let mut point = Point::new(0, 0);
+        point += Direction8::SouthEast;
+        assert_eq!(point, Point::new(1, 1));
+
+        assert_eq!("NW".parse::<Direction8>().unwrap(), Direction8::NorthWest);
+        assert_eq!("v".parse::<Direction8>().unwrap(), Direction8::South);
+        assert!("NNE".parse::<Direction8>().is_err());
+    }
+}
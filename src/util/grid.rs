@@ -0,0 +1,370 @@
+use crate::util::geometry::{Coordinate, Point};
+use anyhow::{bail, Result};
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use std::slice;
+
+/// A position in a grid, as `(x, y)` with `(0, 0)` in the top left corner.
+pub type Position = (usize, usize);
+
+const OFFSETS_4: [(i64, i64); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+const OFFSETS_8: [(i64, i64); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+/// A dense rectangular grid, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// A grid with every cell set to `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+}
+
+impl Grid<char> {
+    /// Parses a grid with a cell per character and a row per line.
+    pub fn parse(input: &str) -> Result<Grid<char>> {
+        Grid::parse_with(input, Ok)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Parses a grid with a cell per character and a row per line, mapping every
+    /// character to a cell. Fails if the grid is empty or not rectangular.
+    pub fn parse_with(input: &str, mut cell: impl FnMut(char) -> Result<T>) -> Result<Grid<T>> {
+        let mut cells = Vec::with_capacity(input.len());
+        let mut width = None;
+        let mut height = 0;
+
+        for (y, line) in input.lines().enumerate() {
+            let before = cells.len();
+            for (x, c) in line.chars().enumerate() {
+                match cell(c) {
+                    Ok(value) => cells.push(value),
+                    Err(err) => bail!("unexpected {:?} at {},{}: {}", c, x, y, err),
+                }
+            }
+
+            let row_width = cells.len() - before;
+            match width {
+                None => width = Some(row_width),
+                Some(width) if width != row_width => bail!(
+                    "row {} is {} cells wide but the first row is {}",
+                    y + 1,
+                    row_width,
+                    width
+                ),
+                _ => {}
+            }
+            height += 1;
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Grid {
+                width,
+                height,
+                cells,
+            }),
+            _ => bail!("the grid is empty"),
+        }
+    }
+
+    /// Builds a grid from its rows, which must all be equally long.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Grid<T>> {
+        let width = rows.first().map_or(0, Vec::len);
+        if width == 0 {
+            bail!("the grid is empty");
+        }
+        if let Some(y) = rows.iter().position(|row| row.len() != width) {
+            bail!(
+                "row {} is {} cells wide but the first row is {}",
+                y + 1,
+                rows[y].len(),
+                width
+            );
+        }
+
+        Ok(Grid {
+            width,
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (x, y): Position) -> Option<&T> {
+        (x < self.width && y < self.height).then(|| &self.cells[y * self.width + x])
+    }
+
+    pub fn get_mut(&mut self, (x, y): Position) -> Option<&mut T> {
+        (x < self.width && y < self.height).then(|| &mut self.cells[y * self.width + x])
+    }
+
+    /// Looks up a cell by point, which may lie outside of the grid.
+    pub fn get_point<C: Coordinate>(&self, point: Point<C>) -> Option<&T> {
+        self.get(point.position()?)
+    }
+
+    pub fn get_point_mut<C: Coordinate>(&mut self, point: Point<C>) -> Option<&mut T> {
+        self.get_mut(point.position()?)
+    }
+
+    /// Looks up a cell by signed coordinates, which may lie outside of the grid.
+    pub fn get_signed(&self, x: i64, y: i64) -> Option<&T> {
+        self.position(x, y).map(|position| &self[position])
+    }
+
+    /// Looks up a cell as if the grid repeated infinitely in every direction.
+    pub fn get_wrapping(&self, x: i64, y: i64) -> &T {
+        let x = x.rem_euclid(self.width as i64) as usize;
+        let y = y.rem_euclid(self.height as i64) as usize;
+        &self[(x, y)]
+    }
+
+    /// The position for signed coordinates, if they lie within the grid.
+    pub fn position(&self, x: i64, y: i64) -> Option<Position> {
+        let x = usize::try_from(x).ok().filter(|&x| x < self.width)?;
+        let y = usize::try_from(y).ok().filter(|&y| y < self.height)?;
+        Some((x, y))
+    }
+
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        self.position(x, y).is_some()
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        &mut self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> slice::Chunks<'_, T> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// Every cell, row by row.
+    pub fn cells(&self) -> slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    pub fn cells_mut(&mut self) -> slice::IterMut<'_, T> {
+        self.cells.iter_mut()
+    }
+
+    /// The first position, row by row, whose cell is equal to the value.
+    pub fn find(&self, value: &T) -> Option<Position>
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .find(|(_, cell)| *cell == value)
+            .map(|(position, _)| position)
+    }
+
+    /// The positions above, right of, below and left of a position that lie
+    /// within the grid.
+    pub fn neighbours4(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        self.neighbours(position, &OFFSETS_4)
+    }
+
+    /// The up to eight positions surrounding a position, starting above it and
+    /// going clockwise.
+    pub fn neighbours8(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        self.neighbours(position, &OFFSETS_8)
+    }
+
+    fn neighbours<'a>(
+        &'a self,
+        (x, y): Position,
+        offsets: &'static [(i64, i64)],
+    ) -> impl Iterator<Item = Position> + 'a {
+        offsets
+            .iter()
+            .filter_map(move |(dx, dy)| self.position(x as i64 + dx, y as i64 + dy))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): Position) -> &T {
+        assert!(
+            x < self.width && y < self.height,
+            "{},{} is outside of a {}x{} grid",
+            x,
+            y,
+            self.width,
+            self.height
+        );
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, (x, y): Position) -> &mut T {
+        assert!(
+            x < self.width && y < self.height,
+            "{},{} is outside of a {}x{} grid",
+            x,
+            y,
+            self.width,
+            self.height
+        );
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+/// Shows every row on its own line, the way grids are given as puzzle input.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::grid::Grid;
+    use anyhow::anyhow;
+
+    const INPUT: &str = "0123\n4567\n89ab";
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse(INPUT).unwrap();
+        assert_eq!((grid.width(), grid.height()), (4, 3));
+        assert_eq!(grid[(1, 2)], '9');
+        assert_eq!(grid.to_string(), INPUT);
+
+        let digits = Grid::parse_with("12\n34", |c| {
+            c.to_digit(10).ok_or_else(|| anyhow!("not a digit"))
+        })
+        .unwrap();
+        assert_eq!(digits.cells().sum::<u32>(), 10);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |input| Grid::parse(input).unwrap_err().to_string();
+        assert_eq!(error(""), "the grid is empty");
+        assert_eq!(
+            error("ab\nabc"),
+            "row 2 is 3 cells wide but the first row is 2"
+        );
+
+        let error = Grid::parse_with("12\n3x", |c| {
+            c.to_digit(10).ok_or_else(|| anyhow!("not a digit"))
+        })
+        .unwrap_err();
+        assert_eq!(error.to_string(), "unexpected 'x' at 1,1: not a digit");
+    }
+
+    #[test]
+    fn test_access() {
+        let mut grid = Grid::parse(INPUT).unwrap();
+        assert_eq!(grid.get((4, 0)), None);
+        assert_eq!(grid.get_signed(-1, 0), None);
+        assert_eq!(grid.get_signed(3, 2), Some(&'b'));
+        assert_eq!(grid.get_wrapping(-1, -1), &'b');
+        assert_eq!(grid.get_wrapping(4, 3), &'0');
+
+        grid[(0, 0)] = '#';
+        *grid.get_mut((1, 0)).unwrap() = '#';
+        assert_eq!(grid.row(0), ['#', '#', '2', '3']);
+    }
+
+    #[test]
+    fn test_iteration() {
+        let grid = Grid::parse(INPUT).unwrap();
+        assert_eq!(grid.column(1).collect::<String>(), "159");
+        assert_eq!(grid.columns().count(), 4);
+        assert_eq!(grid.rows().nth(1).unwrap(), ['4', '5', '6', '7']);
+        assert_eq!(grid.positions().nth(5), Some((1, 1)));
+        assert_eq!(grid.iter().last(), Some(((3, 2), &'b')));
+        assert_eq!(grid.find(&'6'), Some((2, 1)));
+        assert_eq!(grid.find(&'x'), None);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::parse(INPUT).unwrap();
+        assert_eq!(
+            grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbours4((1, 1)).count(), 4);
+        assert_eq!(
+            grid.neighbours8((0, 0)).collect::<Vec<_>>(),
+            vec![(1, 0), (1, 1), (0, 1)]
+        );
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+    }
+
+    #[test]
+    fn test_from_rows_and_map() {
+        let grid = Grid::from_rows(vec![vec![1, 2], vec![3, 4]]).unwrap();
+        assert_eq!(grid.map(|cell| cell * 10).to_string(), "1020\n3040");
+        assert!(Grid::from_rows(vec![vec![1, 2], vec![3]]).is_err());
+        assert_eq!(Grid::new(2, 1, 0).to_string(), "00");
+    }
+}
@@ -0,0 +1,223 @@
+use anyhow::{bail, Context, Result};
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Where runs are recorded, one tab-separated record per line.
+pub const HISTORY_PATH: &str = "./history.tsv";
+
+/// The outcome of running one part once.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// The git revision the run was built from, with `-dirty` if the tree had changes.
+    pub revision: String,
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub variant: String,
+    pub answer: String,
+    pub elapsed: Duration,
+    pub machine: String,
+}
+
+impl Record {
+    fn to_line(&self) -> String {
+        [
+            self.timestamp.to_string(),
+            self.revision.clone(),
+            self.year.to_string(),
+            self.day.to_string(),
+            self.part.to_string(),
+            self.variant.clone(),
+            self.answer.clone(),
+            self.elapsed.as_nanos().to_string(),
+            self.machine.clone(),
+        ]
+        .map(|field| field.replace(['\t', '\n', '\r'], " "))
+        .join("\t")
+    }
+
+    fn from_line(line: &str) -> Result<Record> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [timestamp, revision, year, day, part, variant, answer, elapsed, machine] = fields[..]
+        else {
+            bail!("expected 9 fields but found {}", fields.len());
+        };
+
+        Ok(Record {
+            timestamp: timestamp.parse()?,
+            revision: revision.to_string(),
+            year: year.parse()?,
+            day: day.parse()?,
+            part: part.parse()?,
+            variant: variant.to_string(),
+            answer: answer.to_string(),
+            elapsed: Duration::from_nanos(elapsed.parse()?),
+            machine: machine.to_string(),
+        })
+    }
+}
+
+/// Appends records to the history file, creating it if needed.
+pub fn append(path: &str, records: &[Record]) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Could not open {}", path))?;
+
+    for record in records {
+        writeln!(file, "{}", record.to_line())
+            .with_context(|| format!("Could not write to {}", path))?;
+    }
+
+    Ok(())
+}
+
+/// Reads every record in the history file, oldest first. A missing file is an
+/// empty history.
+pub fn load(path: &str) -> Result<Vec<Record>> {
+    if !Path::new(path).exists() {
+        return Ok(Vec::new());
+    }
+
+    fs::read_to_string(path)
+        .with_context(|| format!("Could not read {}", path))?
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            Record::from_line(line).with_context(|| format!("{} line {}", path, i + 1))
+        })
+        .collect()
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// The current git revision, or `unknown` outside of a git checkout.
+pub fn revision() -> String {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+
+    match git(&["rev-parse", "--short", "HEAD"]) {
+        Some(revision) => match git(&["status", "--porcelain", "--untracked-files=no"]) {
+            Some(changes) if !changes.is_empty() => format!("{}-dirty", revision),
+            _ => revision,
+        },
+        None => "unknown".to_string(),
+    }
+}
+
+/// Identifies the machine well enough to tell whether two timings are comparable.
+pub fn machine() -> String {
+    let host = fs::read_to_string("/etc/hostname")
+        .ok()
+        .or_else(|| env::var("HOSTNAME").ok())
+        .or_else(|| env::var("COMPUTERNAME").ok())
+        .map(|host| host.trim().to_string())
+        .filter(|host| !host.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+    let cpus = thread::available_parallelism().map_or(1, |cpus| cpus.get());
+
+    format!(
+        "{} ({}-{}, {} cpus)",
+        host,
+        env::consts::OS,
+        env::consts::ARCH,
+        cpus
+    )
+}
+
+/// Formats a Unix timestamp as a UTC date and time, e.g. `2024-12-13 06:00`.
+pub fn format_timestamp(timestamp: u64) -> String {
+    let (days, seconds) = (timestamp / 86400, timestamp % 86400);
+
+    // Converts days since the epoch to a civil date, see
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::history::{format_timestamp, Record};
+    use std::time::Duration;
+
+    fn record() -> Record {
+        Record {
+            timestamp: 1734069600,
+            revision: "1ab390e".to_string(),
+            year: 2024,
+            day: 13,
+            part: 2,
+            variant: "default".to_string(),
+            answer: "103729094227877".to_string(),
+            elapsed: Duration::from_micros(1500),
+            machine: "box (linux-x86_64, 8 cpus)".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let record = record();
+        assert_eq!(Record::from_line(&record.to_line()).unwrap(), record);
+    }
+
+    #[test]
+    fn test_fields_cannot_break_lines() {
+        let record = Record {
+            answer: "two\nlines\twith tabs".to_string(),
+            ..record()
+        };
+        let parsed = Record::from_line(&record.to_line()).unwrap();
+        assert_eq!(parsed.answer, "two lines with tabs");
+    }
+
+    #[test]
+    fn test_malformed_line() {
+        assert!(Record::from_line("1734069600\t1ab390e").is_err());
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00");
+        assert_eq!(format_timestamp(1734069600), "2024-12-13 06:00");
+        assert_eq!(format_timestamp(951782400), "2000-02-29 00:00");
+    }
+}
@@ -0,0 +1,392 @@
+//! Sets of integers stored as ranges instead of one by one, on a line and in
+//! the plane, for puzzles that cover huge areas a piece at a time.
+
+use crate::util::geometry::Coordinate;
+use std::ops::{Mul, Range};
+
+/// A set of integers stored as sorted, disjoint, half-open ranges. Ranges that
+/// touch are merged, so equal sets always have the same ranges.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: Coordinate> IntervalSet<T> {
+    pub fn new() -> IntervalSet<T> {
+        IntervalSet { ranges: Vec::new() }
+    }
+
+    /// The ranges of the set, sorted and neither overlapping nor touching.
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// How many integers are in the set.
+    pub fn len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::ZERO, |len, range| len + (range.end - range.start))
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|range| range.end <= value);
+        self.ranges.get(i).is_some_and(|range| range.start <= value)
+    }
+
+    /// Adds every integer in the range.
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+
+        // Every range from `first` up to `last` overlaps or touches the new one
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+        let merged = if first < last {
+            range.start.min(self.ranges[first].start)..range.end.max(self.ranges[last - 1].end)
+        } else {
+            range
+        };
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    /// Removes every integer in the range.
+    pub fn remove(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+
+        // Every range from `first` up to `last` overlaps the removed one, and
+        // only the parts sticking out at either end are kept
+        let first = self.ranges.partition_point(|r| r.end <= range.start);
+        let last = self.ranges.partition_point(|r| r.start < range.end);
+        if first == last {
+            return;
+        }
+        let left = self.ranges[first].start..range.start;
+        let right = range.end..self.ranges[last - 1].end;
+        self.ranges.splice(
+            first..last,
+            [left, right].into_iter().filter(|part| !part.is_empty()),
+        );
+    }
+
+    /// Adds the integers in the range that aren't in the set and removes the
+    /// ones that are.
+    pub fn toggle(&mut self, range: Range<T>) {
+        let inside = self.intersection(&IntervalSet::from(range.clone()));
+        self.insert(range);
+        for part in inside.ranges {
+            self.remove(part);
+        }
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut union = self.clone();
+        for range in &other.ranges {
+            union.insert(range.clone());
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let overlap = a.start.max(b.start)..a.end.min(b.end);
+            if !overlap.is_empty() {
+                ranges.push(overlap);
+            }
+            // Whichever range ends first can't overlap anything else
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    /// The integers in this set that aren't in the other.
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut difference = self.clone();
+        for range in &other.ranges {
+            difference.remove(range.clone());
+        }
+        difference
+    }
+
+    /// The gaps in the set within the given range.
+    pub fn complement(&self, within: Range<T>) -> IntervalSet<T> {
+        IntervalSet::from(within).difference(self)
+    }
+
+    /// The smallest integer from `value` onwards that isn't in the set.
+    pub fn first_missing(&self, value: T) -> T {
+        let i = self.ranges.partition_point(|range| range.end <= value);
+        match self.ranges.get(i) {
+            Some(range) if range.start <= value => range.end,
+            _ => value,
+        }
+    }
+
+    /// The first range of the set that is at least the given length.
+    pub fn first_fit(&self, length: T) -> Option<Range<T>> {
+        self.ranges
+            .iter()
+            .find(|range| range.end - range.start >= length)
+            .cloned()
+    }
+}
+
+impl<T: Coordinate> Default for IntervalSet<T> {
+    fn default() -> IntervalSet<T> {
+        IntervalSet::new()
+    }
+}
+
+impl<T: Coordinate> From<Range<T>> for IntervalSet<T> {
+    fn from(range: Range<T>) -> IntervalSet<T> {
+        let mut set = IntervalSet::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: Coordinate> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(ranges: I) -> IntervalSet<T> {
+        let mut set = IntervalSet::new();
+        for range in ranges {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+/// A set of cells in the plane stored as the rectangles that were added, which
+/// may overlap. The area is found by sweeping across every x where a rectangle
+/// starts or ends, so it is quadratic in the number of rectangles but
+/// independent of their size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RectangleSet<T> {
+    rectangles: Vec<(Range<T>, Range<T>)>,
+}
+
+impl<T: Coordinate> RectangleSet<T> {
+    pub fn new() -> RectangleSet<T> {
+        RectangleSet {
+            rectangles: Vec::new(),
+        }
+    }
+
+    /// The rectangles as they were added, as ranges of x and y.
+    pub fn rectangles(&self) -> &[(Range<T>, Range<T>)] {
+        &self.rectangles
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rectangles.is_empty()
+    }
+
+    /// Adds every cell with an x and a y in the ranges.
+    pub fn insert(&mut self, x: Range<T>, y: Range<T>) {
+        if !x.is_empty() && !y.is_empty() {
+            self.rectangles.push((x, y));
+        }
+    }
+
+    pub fn contains(&self, x: T, y: T) -> bool {
+        self.rectangles
+            .iter()
+            .any(|(xs, ys)| xs.contains(&x) && ys.contains(&y))
+    }
+
+    pub fn union(&self, other: &RectangleSet<T>) -> RectangleSet<T> {
+        let mut rectangles = self.rectangles.clone();
+        rectangles.extend(other.rectangles.iter().cloned());
+        RectangleSet { rectangles }
+    }
+
+    /// The covered cells in every column of the compressed plane, as the range
+    /// of x it spans and the set of y covered within it. Columns that aren't
+    /// covered at all are left out.
+    pub fn columns(&self) -> Vec<(Range<T>, IntervalSet<T>)> {
+        let mut xs: Vec<T> = self
+            .rectangles
+            .iter()
+            .flat_map(|(x, _)| [x.start, x.end])
+            .collect();
+        xs.sort_unstable();
+        xs.dedup();
+
+        xs.windows(2)
+            .map(|pair| {
+                let covered = self
+                    .rectangles
+                    .iter()
+                    .filter(|(x, _)| x.start <= pair[0] && pair[1] <= x.end)
+                    .map(|(_, y)| y.clone())
+                    .collect();
+                (pair[0]..pair[1], covered)
+            })
+            .filter(|(_, covered): &(Range<T>, IntervalSet<T>)| !covered.is_empty())
+            .collect()
+    }
+
+    /// How many cells are covered by at least one rectangle.
+    pub fn area(&self) -> T
+    where
+        T: Mul<Output = T>,
+    {
+        self.columns()
+            .into_iter()
+            .fold(T::ZERO, |area, (x, covered)| {
+                area + (x.end - x.start) * covered.len()
+            })
+    }
+}
+
+impl<T: Coordinate> Default for RectangleSet<T> {
+    fn default() -> RectangleSet<T> {
+        RectangleSet::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::check::agree;
+    use crate::util::intervals::{IntervalSet, RectangleSet};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut set: IntervalSet<i64> = [5..8, 1..3, 3..4, 10..12].into_iter().collect();
+        assert_eq!(set.ranges(), [1..4, 5..8, 10..12]);
+        assert_eq!(set.len(), 8);
+        assert!(set.contains(7) && !set.contains(8) && !set.contains(4));
+
+        set.insert(2..11);
+        assert_eq!(set, IntervalSet::from(1..12));
+        set.remove(3..5);
+        set.remove(9..9);
+        set.remove(11..20);
+        assert_eq!(set.ranges(), [1..3, 5..11]);
+        set.toggle(0..6);
+        assert_eq!(set.ranges(), [0..1, 3..5, 6..11]);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: IntervalSet<usize> = [0..5, 10..15].into_iter().collect();
+        let b = IntervalSet::from(3..12);
+        assert_eq!(a.union(&b), IntervalSet::from(0..15));
+        assert_eq!(a.intersection(&b).ranges(), [3..5, 10..12]);
+        assert_eq!(a.difference(&b).ranges(), [0..3, 12..15]);
+        assert_eq!(a.complement(2..20).ranges(), [5..10, 15..20]);
+
+        assert_eq!(a.first_missing(0), 5);
+        assert_eq!(a.first_missing(7), 7);
+        assert_eq!(a.first_fit(5), Some(0..5));
+        assert_eq!(a.first_fit(6), None);
+        assert_eq!(a.complement(2..20).first_fit(5), Some(5..10));
+    }
+
+    #[test]
+    fn test_against_hash_set() {
+        // Applies random insertions, removals and toggles of ranges in 0..30
+        agree(
+            300,
+            |rng| {
+                (0..rng.range(0..12))
+                    .map(|_| {
+                        let (a, b) = (rng.range(0..30), rng.range(0..30));
+                        (rng.range(0..3), a.min(b), a.max(b))
+                    })
+                    .collect::<Vec<_>>()
+            },
+            |operations| {
+                let mut set = HashSet::new();
+                for &(operation, start, end) in operations {
+                    for value in start..end {
+                        match operation {
+                            0 => set.insert(value),
+                            1 => set.remove(&value),
+                            _ => set.insert(value) || !set.remove(&value),
+                        };
+                    }
+                }
+                let mut values: Vec<usize> = set.into_iter().collect();
+                values.sort_unstable();
+                values
+            },
+            |operations| {
+                let mut set = IntervalSet::new();
+                for &(operation, start, end) in operations {
+                    match operation {
+                        0 => set.insert(start..end),
+                        1 => set.remove(start..end),
+                        _ => set.toggle(start..end),
+                    }
+                }
+                assert_eq!(
+                    set.ranges().iter().flat_map(|r| r.clone()).count(),
+                    set.len()
+                );
+                set.ranges().iter().flat_map(|r| r.clone()).collect()
+            },
+        );
+    }
+
+    #[test]
+    fn test_rectangle_area() {
+        let mut set = RectangleSet::new();
+        set.insert(0..4, 0..4);
+        set.insert(2..6, 2..6);
+        set.insert(10..11, 0..1);
+        set.insert(3..3, 0..100);
+        assert_eq!(set.area(), 16 + 16 - 4 + 1);
+        assert!(set.contains(5, 5) && !set.contains(5, 1));
+        assert_eq!(set.rectangles().len(), 3);
+
+        let mut other = RectangleSet::new();
+        other.insert(-2i64..0, 0..4);
+        assert_eq!(other.union(&RectangleSet::new()).area(), 8);
+    }
+
+    #[test]
+    fn test_rectangle_area_against_cells() {
+        agree(
+            200,
+            |rng| {
+                (0..rng.range(0..6))
+                    .map(|_| {
+                        let mut span = || {
+                            let (a, b) = (rng.range(0..12), rng.range(0..12));
+                            (a.min(b), a.max(b))
+                        };
+                        (span(), span())
+                    })
+                    .collect::<Vec<_>>()
+            },
+            |rectangles| {
+                let mut cells = HashSet::new();
+                for &((x1, x2), (y1, y2)) in rectangles {
+                    cells.extend((x1..x2).flat_map(|x| (y1..y2).map(move |y| (x, y))));
+                }
+                cells.len()
+            },
+            |rectangles| {
+                let mut set = RectangleSet::new();
+                for &((x1, x2), (y1, y2)) in rectangles {
+                    set.insert(x1..x2, y1..y2);
+                }
+                set.area()
+            },
+        );
+    }
+}
@@ -0,0 +1,330 @@
+//! Exact solutions of systems of linear equations with any number of equations
+//! and unknowns, using Gaussian elimination over [`Rational`]s.
+
+use crate::util::math::{crt, gcd, lcm, mod_inverse};
+use crate::util::rational::Rational;
+use std::iter;
+use std::ops::RangeInclusive;
+
+/// Every solution of a system of linear equations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solutions {
+    /// The equations contradict each other.
+    None,
+    Unique(Vec<Rational>),
+    Infinite(Family),
+}
+
+/// Infinitely many solutions: a particular one plus any combination of the
+/// directions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Family {
+    /// The solution where every free unknown is zero.
+    pub particular: Vec<Rational>,
+    /// One direction for every free unknown, which is one for that unknown and
+    /// zero for the other free ones.
+    pub directions: Vec<Vec<Rational>>,
+    /// The free unknowns, in the order of their directions.
+    pub free: Vec<usize>,
+}
+
+/// The integer solutions of a family with a single direction, which are
+/// `start + k * step` for every integer `k`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntegerLine {
+    pub start: Vec<i128>,
+    pub step: Vec<i128>,
+}
+
+/// Solves the equations `coefficients[i] · x == constants[i]`. Panics if the
+/// rows of coefficients aren't equally long or there isn't a constant for
+/// every row.
+pub fn solve<T: Copy + Into<Rational>>(coefficients: &[Vec<T>], constants: &[T]) -> Solutions {
+    assert_eq!(
+        coefficients.len(),
+        constants.len(),
+        "Expected a constant for every equation"
+    );
+    let unknowns = coefficients.first().map_or(0, Vec::len);
+    let mut rows: Vec<Vec<Rational>> = coefficients
+        .iter()
+        .zip(constants)
+        .map(|(row, &constant)| {
+            assert_eq!(row.len(), unknowns, "Expected equally long equations");
+            row.iter()
+                .map(|&value| value.into())
+                .chain(iter::once(constant.into()))
+                .collect()
+        })
+        .collect();
+
+    // Reduce to row echelon form, with every pivot one and alone in its column
+    let mut pivots = Vec::new();
+    for column in 0..unknowns {
+        let row = pivots.len();
+        let Some(pivot) = (row..rows.len()).find(|&r| !rows[r][column].is_zero()) else {
+            continue;
+        };
+        rows.swap(row, pivot);
+
+        let factor = rows[row][column];
+        for value in &mut rows[row][column..] {
+            *value = *value / factor;
+        }
+        let pivot_row = rows[row].clone();
+        for (other, values) in rows.iter_mut().enumerate() {
+            let factor = values[column];
+            if other != row && !factor.is_zero() {
+                for (value, &pivot) in values[column..].iter_mut().zip(&pivot_row[column..]) {
+                    *value = *value - factor * pivot;
+                }
+            }
+        }
+        pivots.push(column);
+    }
+
+    // Every row without a pivot now reads 0 == constant
+    if rows[pivots.len()..]
+        .iter()
+        .any(|row| !row[unknowns].is_zero())
+    {
+        return Solutions::None;
+    }
+
+    let mut particular = vec![Rational::ZERO; unknowns];
+    for (row, &column) in pivots.iter().enumerate() {
+        particular[column] = rows[row][unknowns];
+    }
+    if pivots.len() == unknowns {
+        return Solutions::Unique(particular);
+    }
+
+    let free: Vec<usize> = (0..unknowns).filter(|c| !pivots.contains(c)).collect();
+    let directions = free
+        .iter()
+        .map(|&f| {
+            let mut direction = vec![Rational::ZERO; unknowns];
+            direction[f] = Rational::ONE;
+            for (row, &column) in pivots.iter().enumerate() {
+                direction[column] = -rows[row][f];
+            }
+            direction
+        })
+        .collect();
+
+    Solutions::Infinite(Family {
+        particular,
+        directions,
+        free,
+    })
+}
+
+impl Solutions {
+    /// Every integer solution where each unknown lies within its bounds, found
+    /// by trying every value of the free unknowns, so the bounds of those should
+    /// be small.
+    pub fn integer_solutions(&self, bounds: &[RangeInclusive<i128>]) -> Vec<Vec<i128>> {
+        let within = |solution: &[Rational]| -> Option<Vec<i128>> {
+            solution
+                .iter()
+                .zip(bounds)
+                .map(|(value, bounds)| value.to_integer().filter(|v| bounds.contains(v)))
+                .collect()
+        };
+
+        match self {
+            Solutions::None => Vec::new(),
+            Solutions::Unique(solution) => within(solution).into_iter().collect(),
+            Solutions::Infinite(family) => {
+                let mut solutions = Vec::new();
+                let mut free = vec![Rational::ZERO; family.free.len()];
+                family.each_free(bounds, 0, &mut free, &mut |free| {
+                    solutions.extend(within(&family.at(free)));
+                });
+                solutions
+            }
+        }
+    }
+}
+
+impl Family {
+    /// The solution for the given values of the free unknowns.
+    pub fn at(&self, free: &[Rational]) -> Vec<Rational> {
+        let mut solution = self.particular.clone();
+        for (direction, &amount) in self.directions.iter().zip(free) {
+            for (value, &offset) in solution.iter_mut().zip(direction) {
+                *value = *value + amount * offset;
+            }
+        }
+        solution
+    }
+
+    /// The integer solutions of a family with a single direction, or `None` if
+    /// there aren't any or the congruences they satisfy don't fit in 64 bits.
+    /// Panics if there is more than one direction.
+    pub fn integer_line(&self) -> Option<IntegerLine> {
+        assert_eq!(self.directions.len(), 1, "Expected a single direction");
+        let direction = &self.directions[0];
+
+        // The free unknown is itself the amount `t` of the direction, so `t` is
+        // an integer, and each unknown adds a congruence that `t` must satisfy
+        let mut congruences = Vec::new();
+        for (&value, &offset) in self.particular.iter().zip(direction) {
+            // Denominators are positive, so they convert to unsigned and back
+            let modulus = lcm(value.denominator() as u128, offset.denominator() as u128)?;
+            let modulus = i128::try_from(modulus).ok()?;
+            let a = offset.checked_mul(Rational::integer(modulus))?;
+            let b = value
+                .checked_neg()?
+                .checked_mul(Rational::integer(modulus))?;
+            let (a, b) = (a.to_integer().unwrap(), b.to_integer().unwrap());
+
+            // a * t ≡ b (mod modulus) can only be solved if the gcd divides b
+            let g = gcd(a.unsigned_abs(), modulus as u128) as i128;
+            if b % g != 0 {
+                return None;
+            }
+            let modulus = i64::try_from(modulus / g).ok()?;
+            let a = (a / g).rem_euclid(modulus as i128) as i64;
+            let inverse = mod_inverse(a, modulus).unwrap();
+            let t = (b / g).rem_euclid(modulus as i128) * inverse as i128 % modulus as i128;
+            congruences.push((t as i64, modulus));
+        }
+
+        let (t, modulus) = crt(congruences)?;
+        let start = self.at(&[Rational::from(t)]);
+        let step = direction
+            .iter()
+            .map(|&offset| offset * Rational::from(modulus));
+        Some(IntegerLine {
+            start: start
+                .iter()
+                .map(|value| value.to_integer().unwrap())
+                .collect(),
+            step: step.map(|value| value.to_integer().unwrap()).collect(),
+        })
+    }
+
+    /// Calls `found` for every combination of free unknowns within their bounds.
+    fn each_free(
+        &self,
+        bounds: &[RangeInclusive<i128>],
+        index: usize,
+        free: &mut [Rational],
+        found: &mut impl FnMut(&[Rational]),
+    ) {
+        let Some(&unknown) = self.free.get(index) else {
+            found(free);
+            return;
+        };
+        for value in bounds[unknown].clone() {
+            free[index] = Rational::from(value);
+            self.each_free(bounds, index + 1, free, found);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::linear::{solve, Family, IntegerLine, Solutions};
+    use crate::util::rational::Rational;
+
+    fn integers(values: &[i128]) -> Vec<Rational> {
+        values.iter().map(|&value| Rational::from(value)).collect()
+    }
+
+    #[test]
+    fn test_unique() {
+        let solutions = solve(
+            &[vec![2, 1, -1], vec![-3, -1, 2], vec![-2, 1, 2]],
+            &[8, -11, -3],
+        );
+        assert_eq!(solutions, Solutions::Unique(integers(&[2, 3, -1])));
+
+        // More equations than unknowns, but they agree
+        let solutions = solve(&[vec![1, 1], vec![1, -1], vec![2, 0]], &[3, 1, 4]);
+        assert_eq!(solutions, Solutions::Unique(integers(&[2, 1])));
+
+        let solutions = solve(&[vec![2, 0], vec![0, 4]], &[1, 1]);
+        assert_eq!(
+            solutions,
+            Solutions::Unique(vec![Rational::new(1, 2), Rational::new(1, 4)])
+        );
+    }
+
+    #[test]
+    fn test_none() {
+        assert_eq!(solve(&[vec![1, 1], vec![2, 2]], &[1, 3]), Solutions::None);
+        assert_eq!(
+            solve(&[vec![1, 1], vec![1, -1], vec![2, 0]], &[3, 1, 5]),
+            Solutions::None
+        );
+    }
+
+    #[test]
+    fn test_infinite() {
+        let Solutions::Infinite(family) = solve(&[vec![1, 2, 3], vec![2, 4, 7]], &[6, 13]) else {
+            panic!("expected infinitely many solutions");
+        };
+        assert_eq!(family.free, [1]);
+        assert_eq!(family.particular, integers(&[3, 0, 1]));
+        assert_eq!(family.directions, [integers(&[-2, 1, 0])]);
+        assert_eq!(family.at(&integers(&[2])), integers(&[-1, 2, 1]));
+
+        assert_eq!(
+            solve::<i64>(&[vec![0, 0]], &[0]),
+            Solutions::Infinite(Family {
+                particular: integers(&[0, 0]),
+                directions: vec![integers(&[1, 0]), integers(&[0, 1])],
+                free: vec![0, 1],
+            })
+        );
+    }
+
+    #[test]
+    fn test_integer_line() {
+        // 2a + 3b == 10
+        let Solutions::Infinite(family) = solve(&[vec![2, 3], vec![4, 6]], &[10, 20]) else {
+            panic!("expected infinitely many solutions");
+        };
+        let line = family.integer_line().unwrap();
+        assert_eq!(line.step, [-3, 2]);
+        assert_eq!(2 * line.start[0] + 3 * line.start[1], 10);
+
+        // 2a + 4b == 5 has no integer solutions
+        let Solutions::Infinite(family) = solve(&[vec![2, 4]], &[5]) else {
+            panic!("expected infinitely many solutions");
+        };
+        assert_eq!(family.integer_line(), None);
+
+        // 6a + 10b == 4 only has integer solutions for every third b
+        let Solutions::Infinite(family) = solve(&[vec![6, 10]], &[4]) else {
+            panic!("expected infinitely many solutions");
+        };
+        let IntegerLine { start, step } = family.integer_line().unwrap();
+        assert_eq!(6 * start[0] + 10 * start[1], 4);
+        assert_eq!(step, [-5, 3]);
+
+        // A congruence modulo 2^70 doesn't fit in 64 bits
+        let family = Family {
+            particular: vec![Rational::new(1, 1 << 70), Rational::ZERO],
+            directions: vec![vec![Rational::ONE, Rational::ONE]],
+            free: vec![1],
+        };
+        assert_eq!(family.integer_line(), None);
+    }
+
+    #[test]
+    fn test_integer_solutions() {
+        let solutions = solve(&[vec![2, 3]], &[12]);
+        let bounds = [0..=10, 0..=10];
+        assert_eq!(
+            solutions.integer_solutions(&bounds),
+            [[6, 0], [3, 2], [0, 4]]
+        );
+
+        let unique = solve(&[vec![2, 0], vec![0, 2]], &[2, 3]);
+        assert!(unique.integer_solutions(&bounds).is_empty());
+        assert!(Solutions::None.integer_solutions(&bounds).is_empty());
+    }
+}
@@ -0,0 +1,353 @@
+//! Number theory for the puzzles that need it. Everything that could overflow
+//! returns `None` instead.
+
+use std::ops::{Div, Rem};
+
+/// How many decimal digits a number has, where zero has one.
+pub fn digits(n: u64) -> u32 {
+    n.checked_ilog10().map_or(1, |log| log + 1)
+}
+
+/// Ten to the given power.
+pub fn pow10(exponent: u32) -> Option<u64> {
+    10u64.checked_pow(exponent)
+}
+
+/// Splits off the given number of low digits, so `split_digits(1234, 1)` is
+/// `(123, 4)`.
+pub fn split_digits(n: u64, low_digits: u32) -> (u64, u64) {
+    match pow10(low_digits) {
+        Some(divisor) => (n / divisor, n % divisor),
+        None => (0, n),
+    }
+}
+
+/// Writes the digits of `b` after those of `a`, so `concat(12, 345)` is `12345`.
+pub fn concat(a: u64, b: u64) -> Option<u64> {
+    a.checked_mul(pow10(digits(b))?)?.checked_add(b)
+}
+
+/// An unsigned integer type that [`gcd`] and [`lcm`] work on.
+pub trait Unsigned: Copy + Eq + Div<Output = Self> + Rem<Output = Self> {
+    const ZERO: Self;
+
+    fn checked_mul(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl Unsigned for $t {
+                const ZERO: Self = 0;
+
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned!(u32, u64, u128, usize);
+
+pub fn gcd<T: Unsigned>(mut a: T, mut b: T) -> T {
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+pub fn lcm<T: Unsigned>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// The greatest common divisor `g` of `a` and `b` along with `x` and `y` such
+/// that `a * x + b * y == g`. `g` is never negative.
+pub fn extended_gcd(a: i64, b: i64) -> Option<(i64, i64, i64)> {
+    let (g, x, y) = extended_gcd_wide(a as i128, b as i128);
+    Some((
+        i64::try_from(g).ok()?,
+        i64::try_from(x).ok()?,
+        i64::try_from(y).ok()?,
+    ))
+}
+
+fn extended_gcd_wide(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The `x` in `0..modulus` with `a * x ≡ 1`, if `a` and the modulus are coprime.
+pub fn mod_inverse(a: i64, modulus: i64) -> Option<i64> {
+    if modulus <= 0 {
+        return None;
+    }
+    let (g, x, _) = extended_gcd_wide(a as i128, modulus as i128);
+    (g == 1).then(|| x.rem_euclid(modulus as i128) as i64)
+}
+
+/// `base` to the power of `exponent` modulo a modulus that must not be zero.
+pub fn mod_pow(base: u64, mut exponent: u64, modulus: u64) -> u64 {
+    assert!(modulus > 0, "The modulus must not be zero");
+    let modulus = modulus as u128;
+    let mut base = base as u128 % modulus;
+    let mut result = 1 % modulus;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exponent >>= 1;
+    }
+    result as u64
+}
+
+/// Solves `x ≡ residue (mod modulus)` for every pair at once with the Chinese
+/// remainder theorem, where the moduli don't have to be coprime. Returns the
+/// smallest non-negative `x` along with the modulus of the combined solution,
+/// or `None` if the congruences contradict each other.
+pub fn crt(congruences: impl IntoIterator<Item = (i64, i64)>) -> Option<(i64, i64)> {
+    let mut x: i128 = 0;
+    let mut modulus: i128 = 1;
+    for (residue, other) in congruences {
+        if other <= 0 {
+            return None;
+        }
+        let (residue, other) = (residue as i128, other as i128);
+
+        // x + modulus * k ≡ residue (mod other) has a solution for k only if
+        // the difference is divisible by the gcd of both moduli
+        let (g, inverse, _) = extended_gcd_wide(modulus, other);
+        let difference = residue - x;
+        if difference % g != 0 {
+            return None;
+        }
+        let step = other / g;
+        let k = (difference / g % step * inverse % step).rem_euclid(step);
+
+        x += modulus * k;
+        modulus = i128::from(i64::try_from(modulus * step).ok()?);
+        x = x.rem_euclid(modulus);
+    }
+
+    Some((x as i64, modulus as i64))
+}
+
+/// The largest number whose square is at most `n`.
+pub fn isqrt(n: u64) -> u64 {
+    let mut root = (n as f64).sqrt() as u64;
+    // The floating point root can be off by one either way for large numbers
+    while root.checked_mul(root).is_none_or(|square| square > n) {
+        root -= 1;
+    }
+    while (root + 1)
+        .checked_mul(root + 1)
+        .is_some_and(|square| square <= n)
+    {
+        root += 1;
+    }
+    root
+}
+
+/// Every divisor of a number, in increasing order.
+pub fn divisors(n: u64) -> Vec<u64> {
+    let mut small = Vec::new();
+    let mut large = Vec::new();
+    for divisor in 1..=isqrt(n) {
+        if n.is_multiple_of(divisor) {
+            small.push(divisor);
+            if divisor != n / divisor {
+                large.push(n / divisor);
+            }
+        }
+    }
+
+    small.extend(large.into_iter().rev());
+    small
+}
+
+/// Every prime up to and including the limit, with the sieve of Eratosthenes.
+pub fn primes(limit: usize) -> Vec<usize> {
+    let mut composite = vec![false; limit + 1];
+    let mut primes = Vec::new();
+    for n in 2..=limit {
+        if composite[n] {
+            continue;
+        }
+        primes.push(n);
+        for multiple in (n.saturating_mul(n)..=limit).step_by(n) {
+            composite[multiple] = true;
+        }
+    }
+
+    primes
+}
+
+/// The smallest prime factor of every number up to and including the limit,
+/// for factorising many small numbers quickly. Zero and one map to themselves.
+pub fn smallest_prime_factors(limit: usize) -> Vec<usize> {
+    let mut factors: Vec<usize> = (0..=limit).collect();
+    for n in 2..=isqrt(limit as u64) as usize {
+        if factors[n] != n {
+            continue;
+        }
+        for multiple in (n * n..=limit).step_by(n) {
+            if factors[multiple] == multiple {
+                factors[multiple] = n;
+            }
+        }
+    }
+
+    factors
+}
+
+/// The prime factors of a number with their multiplicities, in increasing
+/// order, found by trial division.
+pub fn prime_factors(mut n: u64) -> Vec<(u64, u32)> {
+    let mut factors = Vec::new();
+    let mut divisor = 2;
+    while divisor <= n / divisor {
+        let mut count = 0;
+        while n.is_multiple_of(divisor) {
+            n /= divisor;
+            count += 1;
+        }
+        if count > 0 {
+            factors.push((divisor, count));
+        }
+        divisor += if divisor == 2 { 1 } else { 2 };
+    }
+    if n > 1 {
+        factors.push((n, 1));
+    }
+
+    factors
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::check::agree;
+    use crate::util::math::*;
+
+    #[test]
+    fn test_digits() {
+        assert_eq!(digits(0), 1);
+        assert_eq!(digits(9), 1);
+        assert_eq!(digits(10), 2);
+        assert_eq!(digits(u64::MAX), 20);
+
+        assert_eq!(split_digits(1234, 1), (123, 4));
+        assert_eq!(split_digits(1234, 2), (12, 34));
+        assert_eq!(split_digits(1234, 25), (0, 1234));
+
+        assert_eq!(concat(12, 345), Some(12345));
+        assert_eq!(concat(7, 0), Some(70));
+        assert_eq!(concat(u64::MAX / 10, 99), None);
+        assert_eq!(pow10(19), Some(10_000_000_000_000_000_000));
+        assert_eq!(pow10(20), None);
+    }
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(gcd(12u64, 18), 6);
+        assert_eq!(gcd(0u32, 5), 5);
+        assert_eq!(lcm(4u64, 6), Some(12));
+        assert_eq!(lcm(0usize, 6), Some(0));
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+        assert_eq!(gcd(u128::MAX, 3), 3);
+
+        let (g, x, y) = extended_gcd(240, 46).unwrap();
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+        assert_eq!(extended_gcd(-4, 0), Some((4, -1, 0)));
+        assert_eq!(extended_gcd(i64::MIN, 0), None);
+    }
+
+    #[test]
+    fn test_modular_arithmetic() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(u64::MAX, u64::MAX, u64::MAX - 1), 1);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt([(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert_eq!(crt([]), Some((0, 1)));
+        assert_eq!(crt([(0, i64::MAX), (0, i64::MAX - 1)]), None);
+    }
+
+    #[test]
+    fn test_crt_against_brute_force() {
+        agree(
+            300,
+            |rng| {
+                let congruence = |rng: &mut crate::util::rng::Rng| {
+                    let modulus = rng.range(1..13);
+                    (rng.range(0..modulus), modulus)
+                };
+                (congruence(rng), congruence(rng), congruence(rng))
+            },
+            |&(a, b, c)| {
+                let congruences = [a, b, c].map(|(r, m)| (r as i64, m as i64));
+                let modulus = congruences
+                    .iter()
+                    .fold(1, |l, &(_, m)| lcm(l, m as u64).unwrap());
+                (0..modulus as i64)
+                    .find(|x| congruences.iter().all(|&(r, m)| x % m == r % m))
+                    .map(|x| (x, modulus as i64))
+            },
+            |&(a, b, c)| crt([a, b, c].map(|(r, m)| (r as i64, m as i64))),
+        );
+    }
+
+    #[test]
+    fn test_isqrt_and_divisors() {
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(15), 3);
+        assert_eq!(isqrt(16), 4);
+        assert_eq!(isqrt(u64::MAX), u32::MAX as u64);
+        assert_eq!(isqrt((1 << 52) + 1), 1 << 26);
+
+        assert_eq!(divisors(1), [1]);
+        assert_eq!(divisors(36), [1, 2, 3, 4, 6, 9, 12, 18, 36]);
+        assert!(divisors(0).is_empty());
+    }
+
+    #[test]
+    fn test_primes() {
+        assert_eq!(primes(30), [2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert!(primes(1).is_empty());
+
+        let factors = smallest_prime_factors(30);
+        assert_eq!(
+            (factors[1], factors[17], factors[25], factors[28]),
+            (1, 17, 5, 2)
+        );
+        assert_eq!(prime_factors(360), [(2, 3), (3, 2), (5, 1)]);
+        assert!(prime_factors(1).is_empty());
+        assert_eq!(prime_factors(4_294_967_311), [(4_294_967_311, 1)]);
+    }
+}
@@ -0,0 +1,171 @@
+//! A cache for recursive solvers that would otherwise solve the same
+//! subproblem over and over.
+
+use crate::util::trace;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Remembers the value computed for every key. Lookups are also counted as
+/// `memo hits` and `memo misses` when tracing counters.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    hits: u64,
+    misses: u64,
+}
+
+/// How well a [`Memo`] has been doing.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Stats {
+    pub hits: u64,
+    pub misses: u64,
+    /// How many values are cached.
+    pub entries: usize,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Memo<K, V> {
+    pub fn new() -> Memo<K, V> {
+        Memo {
+            cache: HashMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// The cached value for the key, computing it with `compute` first if it
+    /// isn't cached yet. `compute` gets the memo back, so that a recursive
+    /// function can look up smaller keys through it:
+    ///
+    /// ```ignore
+    /// fn ways(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+    ///     memo.get(n, |memo| if n < 2 { 1 } else { ways(memo, n - 1) + ways(memo, n - 2) })
+    /// }
+    /// ```
+    pub fn get(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.lookup(&key) {
+            return value;
+        }
+
+        let value = compute(self);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    /// Like [`Memo::get`], but for a recursive closure instead of a function.
+    /// The closure gets a way to solve other keys through the memo, along with
+    /// the key to solve, and can borrow whatever context it needs:
+    ///
+    /// ```ignore
+    /// let ways = memo.solve(n, &|ways, n| if n < 2 { 1 } else { ways(n - 1) + ways(n - 2) });
+    /// ```
+    pub fn solve<F>(&mut self, key: K, f: &F) -> V
+    where
+        F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+    {
+        if let Some(value) = self.lookup(&key) {
+            return value;
+        }
+
+        let value = f(&mut |next| self.solve(next, f), key.clone());
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    /// The cached value for the key, without computing it or counting a lookup.
+    pub fn cached(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    pub fn stats(&self) -> Stats {
+        Stats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.cache.len(),
+        }
+    }
+
+    /// Forgets every cached value, but keeps the statistics.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    fn lookup(&mut self, key: &K) -> Option<V> {
+        match self.cache.get(key) {
+            Some(value) => {
+                self.hits += 1;
+                trace::count("memo hits", 1);
+                Some(value.clone())
+            }
+            None => {
+                self.misses += 1;
+                trace::count("memo misses", 1);
+                None
+            }
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Default for Memo<K, V> {
+    fn default() -> Memo<K, V> {
+        Memo::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::memo::{Memo, Stats};
+
+    fn fibonacci(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+        memo.get(n, |memo| {
+            if n < 2 {
+                n
+            } else {
+                fibonacci(memo, n - 1) + fibonacci(memo, n - 2)
+            }
+        })
+    }
+
+    #[test]
+    fn test_get() {
+        let mut memo = Memo::new();
+        assert_eq!(fibonacci(&mut memo, 90), 2_880_067_194_370_816_120);
+
+        // Every n is computed once, and found again by n + 2 unless that is 2 or
+        // beyond 90
+        let stats = memo.stats();
+        assert_eq!(
+            stats,
+            Stats {
+                hits: 88,
+                misses: 91,
+                entries: 91
+            }
+        );
+        assert_eq!(memo.cached(&10), Some(&55));
+        memo.clear();
+        assert_eq!(memo.cached(&10), None);
+    }
+
+    #[test]
+    fn test_solve_with_context() {
+        // The number of ways to make each amount from the coins, in any order
+        let coins = [1, 2, 5];
+        let mut memo = Memo::new();
+        let ways = |memo: &mut Memo<usize, u64>, amount| {
+            memo.solve(amount, &|ways, amount| {
+                if amount == 0 {
+                    return 1;
+                }
+                coins
+                    .iter()
+                    .filter(|&&coin| coin <= amount)
+                    .map(|coin| ways(amount - coin))
+                    .sum()
+            })
+        };
+
+        assert_eq!(ways(&mut memo, 5), 9);
+        assert_eq!(ways(&mut memo, 60), 49_203_950_608_909);
+        assert_eq!(memo.stats().entries, 61);
+    }
+}
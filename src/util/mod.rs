@@ -1 +1,41 @@
-pub mod runner;
\ No newline at end of file
+// Modules marked `allow(dead_code)` are the puzzle toolkit: shared by every
+// day, with a complete API rather than only the parts today's solutions use.
+#[allow(dead_code)]
+pub mod bitset;
+#[cfg(test)]
+pub mod check;
+#[allow(dead_code)]
+pub mod counter;
+#[allow(dead_code)]
+pub mod cycle;
+#[allow(dead_code)]
+pub mod disjoint_set;
+#[cfg(test)]
+pub mod fuzz;
+#[allow(dead_code)]
+pub mod geometry;
+#[allow(dead_code)]
+pub mod grid;
+pub mod history;
+#[allow(dead_code)]
+pub mod intervals;
+#[allow(dead_code)]
+pub mod linear;
+#[allow(dead_code)]
+pub mod math;
+#[allow(dead_code)]
+pub mod memo;
+#[allow(dead_code)]
+pub mod parse;
+pub mod plugin;
+pub mod progress;
+#[allow(dead_code)]
+pub mod rational;
+#[allow(dead_code)]
+pub mod regions;
+pub mod rng;
+pub mod runner;
+#[allow(dead_code)]
+pub mod search;
+pub mod shape;
+pub mod trace;
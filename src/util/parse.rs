@@ -0,0 +1,406 @@
+//! Parsers for the shapes puzzle inputs come in, so that every day doesn't split
+//! and convert its input by hand. The iterators borrow from the input instead
+//! of allocating.
+
+use anyhow::{anyhow, bail, Context, Result};
+use std::fmt;
+use std::marker::PhantomData;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+/// An integer type that can be found in text, see [`integers`].
+pub trait Integer: FromStr<Err = ParseIntError> + Copy + Default {
+    /// Whether a `-` right before the digits makes the number negative.
+    const SIGNED: bool;
+}
+
+macro_rules! impl_integer {
+    ($signed:expr; $($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const SIGNED: bool = $signed;
+            }
+        )*
+    };
+}
+
+impl_integer!(true; i8, i16, i32, i64, i128, isize);
+impl_integer!(false; u8, u16, u32, u64, u128, usize);
+
+/// Every integer in the text, in order, skipping everything in between. A `-`
+/// right before the digits negates signed integers and is skipped for unsigned
+/// ones, so `"3-4"` holds `3` and `-4` as `i32` but `3` and `4` as `u32`.
+/// Panics if a number doesn't fit in `T`.
+pub fn integers<T: Integer>(input: &str) -> Integers<'_, T> {
+    Integers {
+        rest: input,
+        integer: PhantomData,
+    }
+}
+
+/// The integers in the text, which must be exactly `N` of them.
+pub fn exact_integers<T: Integer, const N: usize>(input: &str) -> Result<[T; N]> {
+    let mut found = integers::<T>(input);
+    let mut values = [T::default(); N];
+    for (i, value) in values.iter_mut().enumerate() {
+        let token = found
+            .next_token()
+            .ok_or_else(|| anyhow!("expected {} integers but found {} in {:?}", N, i, input))?;
+        *value = token
+            .parse()
+            .with_context(|| format!("{:?} is not a valid integer", token))?;
+    }
+    if found.next_token().is_some() {
+        bail!("expected {} integers but found more in {:?}", N, input);
+    }
+
+    Ok(values)
+}
+
+/// The iterator returned by [`integers`].
+#[derive(Debug, Clone)]
+pub struct Integers<'a, T> {
+    rest: &'a str,
+    integer: PhantomData<T>,
+}
+
+impl<'a, T: Integer> Integers<'a, T> {
+    /// The next number as it is written, including its sign.
+    fn next_token(&mut self) -> Option<&'a str> {
+        let bytes = self.rest.as_bytes();
+        let start = bytes.iter().position(u8::is_ascii_digit)?;
+        let end = bytes[start..]
+            .iter()
+            .position(|b| !b.is_ascii_digit())
+            .map_or(bytes.len(), |digits| start + digits);
+        let negative = T::SIGNED && start > 0 && bytes[start - 1] == b'-';
+
+        let token = &self.rest[start - usize::from(negative)..end];
+        self.rest = &self.rest[end..];
+        Some(token)
+    }
+}
+
+impl<T: Integer> Iterator for Integers<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let token = self.next_token()?;
+        Some(
+            token
+                .parse()
+                .unwrap_or_else(|error| panic!("{:?} is not a valid integer: {}", token, error)),
+        )
+    }
+}
+
+/// The blocks of lines separated by blank lines, without their trailing
+/// newlines. Lines with only whitespace count as blank, and several blank lines
+/// in a row separate only two blocks.
+pub fn blocks(input: &str) -> Blocks<'_> {
+    Blocks { rest: input }
+}
+
+/// The iterator returned by [`blocks`].
+#[derive(Debug, Clone)]
+pub struct Blocks<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Iterator for Blocks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let mut start = None;
+        let mut end = 0;
+        let mut offset = 0;
+        for line in self.rest.split_inclusive('\n') {
+            if !line.trim().is_empty() {
+                start.get_or_insert(offset);
+                end = offset + line.trim_end_matches(['\n', '\r']).len();
+            } else if start.is_some() {
+                break;
+            }
+            offset += line.len();
+        }
+
+        let block = &self.rest[start?..end];
+        self.rest = &self.rest[offset..];
+        Some(block)
+    }
+}
+
+/// Splits a `key: value` line at the first colon, trimming both sides.
+pub fn key_value(line: &str) -> Result<(&str, &str)> {
+    let (key, value) = line
+        .split_once(':')
+        .ok_or_else(|| anyhow!("expected a key and a value but found {:?}", line))?;
+    Ok((key.trim(), value.trim()))
+}
+
+/// Splits every line into a key and a value, see [`key_value`].
+pub fn key_values(input: &str) -> impl Iterator<Item = Result<(&str, &str)>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| key_value(line).with_context(|| format!("line {}", i + 1)))
+}
+
+/// Parses every line with `FromStr`. Errors name the line that failed.
+pub fn parse_lines<T>(input: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Into<anyhow::Error>,
+{
+    parse_lines_with(input, |line| line.parse().map_err(Into::into))
+}
+
+/// Parses every line with `parse`. Errors name the line that failed.
+pub fn parse_lines_with<T>(
+    input: &str,
+    mut parse: impl FnMut(&str) -> Result<T>,
+) -> Result<Vec<T>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parse(line).with_context(|| format!("line {}", i + 1)))
+        .collect()
+}
+
+/// A line of integers with the separator between each of them, like `1,2,3`.
+pub fn separated<T: Integer>(line: &str, separator: &str) -> Result<Vec<T>> {
+    let mut scanner = Scanner::new(line);
+    let values = scanner.separated(separator)?;
+    scanner.finish()?;
+    Ok(values)
+}
+
+/// Reads a structured line from left to right, piece by piece:
+///
+/// ```ignore
+/// let mut scanner = Scanner::new("turn on 0,0 through 999,999");
+/// scanner.literal("turn ")?;
+/// let state = scanner.one_of(&["on", "off"])?;
+/// scanner.literal(" ")?;
+/// let from = scanner.pair::<usize>(",")?;
+/// scanner.literal(" through ")?;
+/// let to = scanner.pair::<usize>(",")?;
+/// scanner.finish()?;
+/// ```
+///
+/// Errors point at the column where reading failed.
+#[derive(Debug, Clone)]
+pub struct Scanner<'a> {
+    line: &'a str,
+    rest: &'a str,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(line: &'a str) -> Scanner<'a> {
+        Scanner { line, rest: line }
+    }
+
+    /// Everything that hasn't been read yet.
+    pub fn rest(&self) -> &'a str {
+        self.rest
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.rest.is_empty()
+    }
+
+    /// Reads the literal if it comes next, or reads nothing.
+    pub fn try_literal(&mut self, literal: &str) -> bool {
+        match self.rest.strip_prefix(literal) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Reads the literal, which must come next.
+    pub fn literal(&mut self, literal: &str) -> Result<()> {
+        if self.try_literal(literal) {
+            Ok(())
+        } else {
+            Err(self.error(format_args!("expected {:?}", literal)))
+        }
+    }
+
+    /// Reads the first of the options that comes next. Options that start with
+    /// another option must come before it.
+    pub fn one_of<'o>(&mut self, options: &[&'o str]) -> Result<&'o str> {
+        match options
+            .iter()
+            .find(|option| self.rest.starts_with(**option))
+        {
+            Some(option) => {
+                self.rest = &self.rest[option.len()..];
+                Ok(option)
+            }
+            None => Err(self.error(format_args!("expected one of {:?}", options))),
+        }
+    }
+
+    /// Reads everything up to the next whitespace or the end of the line, which
+    /// must not be nothing, and skips the whitespace after it.
+    pub fn word(&mut self) -> Result<&'a str> {
+        let end = self
+            .rest
+            .find(char::is_whitespace)
+            .unwrap_or(self.rest.len());
+        if end == 0 {
+            return Err(self.error("expected a word"));
+        }
+
+        let word = &self.rest[..end];
+        self.rest = self.rest[end..].trim_start();
+        Ok(word)
+    }
+
+    /// Reads everything up to the separator, and the separator itself.
+    pub fn until(&mut self, separator: &str) -> Result<&'a str> {
+        let (before, after) = self
+            .rest
+            .split_once(separator)
+            .ok_or_else(|| self.error(format_args!("expected {:?}", separator)))?;
+        self.rest = after;
+        Ok(before)
+    }
+
+    /// Reads an integer, which may start with a `-` if `T` is signed.
+    pub fn integer<T: Integer>(&mut self) -> Result<T> {
+        let bytes = self.rest.as_bytes();
+        let sign = usize::from(T::SIGNED && bytes.first() == Some(&b'-'));
+        let digits = bytes[sign..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        if digits == 0 {
+            return Err(self.error("expected an integer"));
+        }
+
+        let (token, rest) = self.rest.split_at(sign + digits);
+        let value = token.parse().map_err(|error| {
+            self.error(format_args!(
+                "{:?} is not a valid integer ({})",
+                token, error
+            ))
+        })?;
+        self.rest = rest;
+        Ok(value)
+    }
+
+    /// Reads two integers with the separator between them, like `3,4`.
+    pub fn pair<T: Integer>(&mut self, separator: &str) -> Result<(T, T)> {
+        let first = self.integer()?;
+        self.literal(separator)?;
+        Ok((first, self.integer()?))
+    }
+
+    /// Reads one or more integers with the separator between each of them.
+    pub fn separated<T: Integer>(&mut self, separator: &str) -> Result<Vec<T>> {
+        let mut values = vec![self.integer()?];
+        while self.try_literal(separator) {
+            values.push(self.integer()?);
+        }
+        Ok(values)
+    }
+
+    /// Skips any whitespace.
+    pub fn whitespace(&mut self) {
+        self.rest = self.rest.trim_start();
+    }
+
+    /// Checks that the whole line has been read.
+    pub fn finish(&self) -> Result<()> {
+        if self.is_done() {
+            Ok(())
+        } else {
+            Err(self.error("expected the end of the line"))
+        }
+    }
+
+    fn error(&self, expected: impl fmt::Display) -> anyhow::Error {
+        let column = self.line.len() - self.rest.len() + 1;
+        anyhow!("{} at column {} of {:?}", expected, column, self.line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::parse::*;
+
+    #[test]
+    fn test_integers() {
+        let text = "p=3,-4 v=-12-5";
+        assert_eq!(integers::<i32>(text).collect::<Vec<_>>(), [3, -4, -12, -5]);
+        assert_eq!(integers::<u8>(text).collect::<Vec<_>>(), [3, 4, 12, 5]);
+        assert_eq!(integers::<u64>("none").count(), 0);
+
+        assert_eq!(exact_integers::<u32, 2>("X+94, Y+34").unwrap(), [94, 34]);
+        assert!(exact_integers::<u32, 2>("X+94").is_err());
+        assert!(exact_integers::<u32, 2>("1 2 3").is_err());
+        assert!(exact_integers::<u8, 1>("256").is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "not a valid integer")]
+    fn test_integers_overflow() {
+        integers::<u8>("1 256").for_each(drop);
+    }
+
+    #[test]
+    fn test_blocks() {
+        let text = "\na\nb\n\n\n  \nc\r\n\r\nd\n";
+        assert_eq!(blocks(text).collect::<Vec<_>>(), ["a\nb", "c", "d"]);
+        assert_eq!(blocks("\n\n").count(), 0);
+        assert_eq!(blocks("only").collect::<Vec<_>>(), ["only"]);
+    }
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(key_value("Button A: X+1").unwrap(), ("Button A", "X+1"));
+        let error = key_values("a: 1\nb")
+            .collect::<Result<Vec<_>>>()
+            .unwrap_err();
+        assert_eq!(
+            format!("{:#}", error),
+            "line 2: expected a key and a value but found \"b\""
+        );
+
+        assert_eq!(parse_lines::<u16>("1\n2\n3").unwrap(), [1, 2, 3]);
+        assert_eq!(separated::<i8>("1,-2,3", ",").unwrap(), [1, -2, 3]);
+        assert!(separated::<i8>("1,2,", ",").is_err());
+        let error = parse_lines::<u16>("1\nx").unwrap_err();
+        assert_eq!(
+            format!("{:#}", error),
+            "line 2: invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn test_scanner() {
+        let mut scanner = Scanner::new("toggle 0,-5 through 999,999: 1 2 3");
+        assert!(!scanner.try_literal("turn "));
+        assert_eq!(scanner.one_of(&["on", "off", "toggle"]).unwrap(), "toggle");
+        scanner.whitespace();
+        assert_eq!(scanner.pair::<i32>(",").unwrap(), (0, -5));
+        assert_eq!(scanner.until(":").unwrap(), " through 999,999");
+        scanner.whitespace();
+        assert_eq!(scanner.separated::<u8>(" ").unwrap(), [1, 2, 3]);
+        scanner.finish().unwrap();
+
+        let mut scanner = Scanner::new("turn -1");
+        assert_eq!(scanner.word().unwrap(), "turn");
+        let error = scanner.integer::<u32>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "expected an integer at column 6 of \"turn -1\""
+        );
+        assert_eq!(scanner.rest(), "-1");
+        assert!(scanner.finish().is_err());
+    }
+}
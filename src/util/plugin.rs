@@ -0,0 +1,267 @@
+//! Solutions loaded at runtime from `cdylib` plugins, so that an implementation
+//! living outside of this repository can be run, verified and benchmarked like
+//! an in-tree variant.
+//!
+//! A plugin exports two functions with the C ABI:
+//!
+//! ```c
+//! uint32_t aoc_abi_version(void);
+//! const Solution *aoc_solutions(size_t *count);
+//! ```
+//!
+//! `aoc_abi_version` must return [`ABI_VERSION`] and `aoc_solutions` returns an
+//! array of [`Solution`] that stays valid for as long as the plugin is loaded.
+//! See `plugins/example` for a plugin written in Rust.
+
+use crate::util::runner::{Answer, Runner, Variant};
+use anyhow::{anyhow, bail, Context, Result};
+use libloading::Library;
+use std::env::consts::DLL_EXTENSION;
+use std::ffi::{c_char, CStr};
+use std::fs;
+
+/// Bumped whenever [`Solution`] or [`SolveFn`] change.
+pub const ABI_VERSION: u32 = 1;
+
+/// How many bytes a plugin may write as its answer.
+pub const ANSWER_CAPACITY: usize = 1024;
+
+/// Solves a part for the UTF-8 input of `input_len` bytes at `input`, writing
+/// the answer to the buffer at `answer`, which holds `answer_capacity` bytes.
+/// Returns the length of the answer, or a negative number if the part couldn't
+/// be solved.
+pub type SolveFn = unsafe extern "C" fn(
+    input: *const u8,
+    input_len: usize,
+    answer: *mut u8,
+    answer_capacity: usize,
+) -> isize;
+
+/// One variant of a part, as described by a plugin.
+#[repr(C)]
+pub struct Solution {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    /// The variant name as a nul-terminated UTF-8 string.
+    pub name: *const c_char,
+    pub solve: SolveFn,
+}
+
+/// Adds the variants every plugin in the directory has for the runner's day.
+/// Plugins are never unloaded, since their variants may be used until the end
+/// of the run.
+pub fn install(runner: &mut Runner, dir: &str) -> Result<()> {
+    for path in plugin_paths(dir)? {
+        // SAFETY: loading a plugin runs its initialisation code, which has to be
+        // trusted just like the code of the runner itself
+        let library = unsafe { Library::new(&path) }
+            .with_context(|| format!("Could not load plugin {}", path))?;
+        let library: &'static Library = Box::leak(Box::new(library));
+
+        // SAFETY: the plugin is never unloaded, so the solutions stay valid
+        let solutions =
+            unsafe { solutions(library) }.with_context(|| format!("Plugin {}", path))?;
+        add_variants(runner, solutions).with_context(|| format!("Plugin {}", path))?;
+    }
+
+    Ok(())
+}
+
+/// Every file in the directory that looks like a dynamic library, in name order.
+fn plugin_paths(dir: &str) -> Result<Vec<String>> {
+    let entries =
+        fs::read_dir(dir).with_context(|| format!("Could not read plugin directory {}", dir))?;
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == DLL_EXTENSION) {
+            paths.push(path.display().to_string());
+        }
+    }
+
+    paths.sort();
+    Ok(paths)
+}
+
+/// # Safety
+///
+/// The library must stay loaded for as long as the solutions are used.
+unsafe fn solutions(library: &'static Library) -> Result<&'static [Solution]> {
+    let abi_version = library
+        .get::<unsafe extern "C" fn() -> u32>(b"aoc_abi_version")
+        .context("Missing aoc_abi_version")?;
+    let version = abi_version();
+    if version != ABI_VERSION {
+        bail!(
+            "Built for plugin ABI version {}, but the runner uses version {}",
+            version,
+            ABI_VERSION
+        );
+    }
+
+    let get_solutions = library
+        .get::<unsafe extern "C" fn(*mut usize) -> *const Solution>(b"aoc_solutions")
+        .context("Missing aoc_solutions")?;
+    let mut count = 0;
+    let solutions = get_solutions(&mut count);
+    if solutions.is_null() || count == 0 {
+        return Ok(&[]);
+    }
+
+    Ok(std::slice::from_raw_parts(solutions, count))
+}
+
+/// Adds the solutions for the runner's day as variants, refusing names that are
+/// already taken.
+fn add_variants(runner: &mut Runner, solutions: &'static [Solution]) -> Result<()> {
+    for solution in solutions {
+        if (solution.year, solution.day) != (runner.year, runner.day) {
+            continue;
+        }
+
+        let name = variant_name(solution)?;
+        let variants = match solution.part {
+            1 => &mut runner.part1,
+            2 => &mut runner.part2,
+            part => bail!("Variant {} is for unknown part {}", name, part),
+        };
+        if variants.iter().any(|variant| variant.name == name) {
+            bail!(
+                "Part {} already has a variant named {}",
+                solution.part,
+                name
+            );
+        }
+
+        let (solve, part) = (solution.solve, solution.part);
+        variants.push(Variant {
+            name,
+            solve: Box::new(move |input, _| {
+                call(solve, input).unwrap_or_else(|err| panic!("{} part {}: {}", name, part, err))
+            }),
+        });
+    }
+
+    Ok(())
+}
+
+fn variant_name(solution: &'static Solution) -> Result<&'static str> {
+    if solution.name.is_null() {
+        bail!("Variant for part {} has no name", solution.part);
+    }
+
+    // SAFETY: the plugin promises a nul-terminated string that lives as long as
+    // the plugin, which is never unloaded
+    let name = unsafe { CStr::from_ptr(solution.name) };
+    name.to_str()
+        .map_err(|_| anyhow!("Variant name {:?} is not valid UTF-8", name))
+}
+
+fn call(solve: SolveFn, input: &str) -> Result<Answer> {
+    let mut answer = vec![0; ANSWER_CAPACITY];
+
+    // SAFETY: both buffers are valid for the lengths passed along with them
+    let len = unsafe {
+        solve(
+            input.as_ptr(),
+            input.len(),
+            answer.as_mut_ptr(),
+            answer.len(),
+        )
+    };
+    let len = usize::try_from(len).map_err(|_| anyhow!("the plugin failed with {}", len))?;
+    if len > ANSWER_CAPACITY {
+        bail!("the answer is longer than {} bytes", ANSWER_CAPACITY);
+    }
+
+    answer.truncate(len);
+    let answer = String::from_utf8(answer).context("the answer is not valid UTF-8")?;
+    Ok(answer.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::plugin::{add_variants, Solution};
+    use crate::util::runner::{Answer, Runner};
+    use std::ptr;
+    use std::slice;
+
+    unsafe extern "C" fn length(
+        input: *const u8,
+        input_len: usize,
+        answer: *mut u8,
+        answer_capacity: usize,
+    ) -> isize {
+        let _ = slice::from_raw_parts(input, input_len);
+        let length = input_len.to_string();
+        let answer = slice::from_raw_parts_mut(answer, answer_capacity);
+        answer[..length.len()].copy_from_slice(length.as_bytes());
+        length.len() as isize
+    }
+
+    unsafe extern "C" fn fail(_: *const u8, _: usize, _: *mut u8, _: usize) -> isize {
+        -1
+    }
+
+    fn solution(day: u8, part: u8, name: &'static std::ffi::CStr) -> Solution {
+        Solution {
+            year: 2024,
+            day,
+            part,
+            name: name.as_ptr(),
+            solve: length,
+        }
+    }
+
+    fn leak(solutions: Vec<Solution>) -> &'static [Solution] {
+        Box::leak(solutions.into_boxed_slice())
+    }
+
+    #[test]
+    fn test_adds_variants_for_the_day() {
+        let mut runner = Runner::new(2024, 1).part1("default", |_, _| 0.into());
+        let solutions = leak(vec![
+            solution(1, 1, c"plugin"),
+            solution(1, 2, c"plugin"),
+            solution(2, 1, c"other day"),
+        ]);
+        add_variants(&mut runner, solutions).unwrap();
+
+        assert_eq!(runner.part1.len(), 2);
+        assert_eq!(runner.part2.len(), 1);
+        let (answer, _) = runner.variant(1, Some("plugin")).unwrap().run("abc");
+        assert_eq!(answer, Answer::from(3));
+    }
+
+    #[test]
+    fn test_rejects_taken_names_and_unknown_parts() {
+        let mut runner = Runner::new(2024, 1).part1("default", |_, _| 0.into());
+        let error = add_variants(&mut runner, leak(vec![solution(1, 1, c"default")])).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Part 1 already has a variant named default"
+        );
+
+        let error = add_variants(&mut runner, leak(vec![solution(1, 3, c"plugin")])).unwrap_err();
+        assert_eq!(error.to_string(), "Variant plugin is for unknown part 3");
+
+        let unnamed = Solution {
+            name: ptr::null(),
+            ..solution(1, 2, c"")
+        };
+        assert!(add_variants(&mut runner, leak(vec![unnamed])).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "broken part 2: the plugin failed with -1")]
+    fn test_failing_plugin() {
+        let mut runner = Runner::new(2024, 1);
+        let broken = Solution {
+            solve: fail,
+            ..solution(1, 2, c"broken")
+        };
+        add_variants(&mut runner, leak(vec![broken])).unwrap();
+        runner.part2[0].run("abc");
+    }
+}
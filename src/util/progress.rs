@@ -0,0 +1,177 @@
+use std::io::{self, IsTerminal};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often the progress line is redrawn.
+const REFRESH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Handed to every solver so that long searches can report how far along they
+/// are and stop early once the run is cancelled. Reporting is a relaxed atomic
+/// store, cheap enough to do from a hot loop.
+#[derive(Default)]
+pub struct Progress {
+    done: AtomicU64,
+    total: AtomicU64,
+    cancelled: AtomicBool,
+}
+
+impl Progress {
+    pub fn new() -> Progress {
+        Progress::default()
+    }
+
+    /// Declares how many steps the search will take, if that is known up front.
+    pub fn set_total(&self, total: u64) {
+        self.total.store(total, Ordering::Relaxed);
+    }
+
+    pub fn set(&self, done: u64) {
+        self.done.store(done, Ordering::Relaxed);
+    }
+
+    pub fn advance(&self, amount: u64) {
+        self.done.fetch_add(amount, Ordering::Relaxed);
+    }
+
+    /// Asks the solver to stop. Returns whether the run was already cancelled.
+    pub fn cancel(&self) -> bool {
+        self.cancelled.swap(true, Ordering::Relaxed)
+    }
+
+    /// Solvers that check this should return as soon as it is set, with any answer:
+    /// the answer of a cancelled part is never shown.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Clears the reported progress before the next part, keeping a cancellation.
+    pub fn reset(&self) {
+        self.done.store(0, Ordering::Relaxed);
+        self.total.store(0, Ordering::Relaxed);
+    }
+
+    /// The progress line, or nothing if the solver hasn't reported anything.
+    pub fn status(&self, label: &str) -> Option<String> {
+        let done = self.done.load(Ordering::Relaxed);
+        let total = self.total.load(Ordering::Relaxed);
+
+        match (done, total) {
+            (0, 0) => None,
+            (done, 0) => Some(format!("{}: {}", label, done)),
+            (done, total) => Some(format!(
+                "{}: {}/{} ({:.0}%)",
+                label,
+                done,
+                total,
+                100.0 * done as f64 / total as f64
+            )),
+        }
+    }
+}
+
+/// Runs `f` while redrawing a progress line on stderr, if stderr is a terminal.
+pub fn show_while<T>(progress: &Progress, label: &str, f: impl FnOnce() -> T) -> T {
+    if !io::stderr().is_terminal() {
+        return f();
+    }
+
+    let finished = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        let reporter = scope.spawn(|| {
+            let mut shown = false;
+            while !finished.load(Ordering::Relaxed) {
+                if let Some(status) = progress.status(label) {
+                    eprint!("\r\x1b[K{}", status);
+                    shown = true;
+                }
+                thread::park_timeout(REFRESH_INTERVAL);
+            }
+            if shown {
+                eprint!("\r\x1b[K");
+            }
+        });
+
+        let result = f();
+        finished.store(true, Ordering::Relaxed);
+        reporter.thread().unpark();
+        result
+    })
+}
+
+/// Runs `f`, cancelling the progress if it takes longer than the timeout. Only
+/// solvers that check for cancellation actually stop early.
+pub fn cancel_after<T>(progress: &Progress, timeout: Duration, f: impl FnOnce() -> T) -> T {
+    let finished = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        let watchdog = scope.spawn(|| {
+            let start = Instant::now();
+            while !finished.load(Ordering::Relaxed) {
+                match timeout.checked_sub(start.elapsed()) {
+                    Some(remaining) if !remaining.is_zero() => thread::park_timeout(remaining),
+                    _ => {
+                        progress.cancel();
+                        break;
+                    }
+                }
+            }
+        });
+
+        let result = f();
+        finished.store(true, Ordering::Relaxed);
+        watchdog.thread().unpark();
+        result
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::progress::{cancel_after, Progress};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_status() {
+        let progress = Progress::new();
+        assert_eq!(progress.status("Part 1"), None);
+
+        progress.advance(3);
+        progress.advance(4);
+        assert_eq!(progress.status("Part 1").unwrap(), "Part 1: 7");
+
+        progress.set_total(28);
+        assert_eq!(progress.status("Part 1").unwrap(), "Part 1: 7/28 (25%)");
+    }
+
+    #[test]
+    fn test_reset_keeps_cancellation() {
+        let progress = Progress::new();
+        progress.set(10);
+        assert!(!progress.cancel());
+        assert!(progress.cancel());
+
+        progress.reset();
+        assert_eq!(progress.status("Part 2"), None);
+        assert!(progress.is_cancelled());
+    }
+
+    #[test]
+    fn test_cancel_after() {
+        let progress = Progress::new();
+        let steps = cancel_after(&progress, Duration::from_millis(20), || {
+            let mut steps = 0;
+            while !progress.is_cancelled() {
+                thread::sleep(Duration::from_millis(1));
+                steps += 1;
+            }
+            steps
+        });
+        assert!(steps > 0);
+
+        let progress = Progress::new();
+        cancel_after(&progress, Duration::from_secs(10), || ());
+        assert!(!progress.is_cancelled());
+    }
+}
@@ -0,0 +1,331 @@
+//! Exact fractions, for solving equations without rounding errors.
+
+use crate::util::math::gcd;
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// A fraction in lowest terms with a positive denominator, so that equal values
+/// are always represented the same way. Arithmetic panics on overflow, like
+/// integer arithmetic in debug builds, unless the `checked_` variants are used.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational::integer(0);
+    pub const ONE: Rational = Rational::integer(1);
+
+    /// Panics if the denominator is zero.
+    pub fn new(numerator: i128, denominator: i128) -> Rational {
+        Rational::checked_new(numerator, denominator).expect("a non-zero denominator")
+    }
+
+    /// Reduces the fraction, or returns `None` if the denominator is zero or the
+    /// reduced fraction doesn't fit.
+    pub fn checked_new(numerator: i128, denominator: i128) -> Option<Rational> {
+        if denominator == 0 {
+            return None;
+        }
+
+        let divisor = gcd(numerator.unsigned_abs(), denominator.unsigned_abs()) as i128;
+        let (numerator, denominator) = (numerator / divisor, denominator / divisor);
+        if denominator < 0 {
+            Some(Rational {
+                numerator: numerator.checked_neg()?,
+                denominator: denominator.checked_neg()?,
+            })
+        } else {
+            Some(Rational {
+                numerator,
+                denominator,
+            })
+        }
+    }
+
+    pub const fn integer(value: i128) -> Rational {
+        Rational {
+            numerator: value,
+            denominator: 1,
+        }
+    }
+
+    pub fn numerator(self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(self) -> i128 {
+        self.denominator
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.numerator == 0
+    }
+
+    pub fn is_integer(self) -> bool {
+        self.denominator == 1
+    }
+
+    /// The value as an integer, if it is one.
+    pub fn to_integer(self) -> Option<i128> {
+        self.is_integer().then_some(self.numerator)
+    }
+
+    /// The largest integer that isn't greater than the value.
+    pub fn floor(self) -> i128 {
+        self.numerator.div_euclid(self.denominator)
+    }
+
+    /// The smallest integer that isn't smaller than the value.
+    pub fn ceil(self) -> i128 {
+        -(-self.numerator).div_euclid(self.denominator)
+    }
+
+    /// The fractional part, which is always in `0..1`.
+    pub fn fract(self) -> Rational {
+        Rational {
+            numerator: self.numerator.rem_euclid(self.denominator),
+            denominator: self.denominator,
+        }
+    }
+
+    /// One divided by the value, or `None` if it is zero.
+    pub fn recip(self) -> Option<Rational> {
+        Rational::checked_new(self.denominator, self.numerator)
+    }
+
+    pub fn abs(self) -> Rational {
+        if self.numerator < 0 {
+            -self
+        } else {
+            self
+        }
+    }
+
+    pub fn checked_add(self, other: Rational) -> Option<Rational> {
+        let divisor = gcd(
+            self.denominator.unsigned_abs(),
+            other.denominator.unsigned_abs(),
+        ) as i128;
+        let left = self.numerator.checked_mul(other.denominator / divisor)?;
+        let right = other.numerator.checked_mul(self.denominator / divisor)?;
+        let denominator = (self.denominator / divisor).checked_mul(other.denominator)?;
+        Rational::checked_new(left.checked_add(right)?, denominator)
+    }
+
+    pub fn checked_sub(self, other: Rational) -> Option<Rational> {
+        self.checked_add(other.checked_neg()?)
+    }
+
+    pub fn checked_mul(self, other: Rational) -> Option<Rational> {
+        // Cancel crosswise first so that the products stay as small as possible.
+        // Denominators are positive, so neither divisor can be zero
+        let a = gcd(
+            self.numerator.unsigned_abs(),
+            other.denominator.unsigned_abs(),
+        ) as i128;
+        let b = gcd(
+            other.numerator.unsigned_abs(),
+            self.denominator.unsigned_abs(),
+        ) as i128;
+        let numerator = (self.numerator / a).checked_mul(other.numerator / b)?;
+        let denominator = (self.denominator / b).checked_mul(other.denominator / a)?;
+        Rational::checked_new(numerator, denominator)
+    }
+
+    /// Returns `None` on overflow or when dividing by zero.
+    pub fn checked_div(self, other: Rational) -> Option<Rational> {
+        self.checked_mul(other.recip()?)
+    }
+
+    pub fn checked_neg(self) -> Option<Rational> {
+        Some(Rational {
+            numerator: self.numerator.checked_neg()?,
+            denominator: self.denominator,
+        })
+    }
+}
+
+impl Default for Rational {
+    fn default() -> Rational {
+        Rational::ZERO
+    }
+}
+
+macro_rules! impl_rational_from {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Rational {
+                fn from(value: $t) -> Rational {
+                    Rational::integer(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_rational_from!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, rhs: Rational) -> Rational {
+        self.checked_add(rhs)
+            .expect("rational addition to not overflow")
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, rhs: Rational) -> Rational {
+        self.checked_sub(rhs)
+            .expect("rational subtraction to not overflow")
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, rhs: Rational) -> Rational {
+        self.checked_mul(rhs)
+            .expect("rational multiplication to not overflow")
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    fn div(self, rhs: Rational) -> Rational {
+        assert!(!rhs.is_zero(), "Division by zero");
+        self.checked_div(rhs)
+            .expect("rational division to not overflow")
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        self.checked_neg()
+            .expect("rational negation to not overflow")
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Rational) -> Ordering {
+        // Both denominators are positive, so cross multiplying keeps the order
+        let left = self.numerator.checked_mul(other.denominator);
+        let right = other.numerator.checked_mul(self.denominator);
+        match (left, right) {
+            (Some(left), Some(right)) => left.cmp(&right),
+            _ => compare_expansions(*self, *other),
+        }
+    }
+}
+
+/// Compares two fractions without multiplying them, by comparing their
+/// continued fraction expansions one term at a time.
+fn compare_expansions(left: Rational, right: Rational) -> Ordering {
+    let (mut a, mut b) = (left.numerator, left.denominator);
+    let (mut c, mut d) = (right.numerator, right.denominator);
+    let mut reversed = false;
+    loop {
+        let (p, q) = (a.div_euclid(b), c.div_euclid(d));
+        let (r, s) = (a.rem_euclid(b), c.rem_euclid(d));
+        let ordering = match (p.cmp(&q), r, s) {
+            (Ordering::Equal, 0, 0) => Ordering::Equal,
+            (Ordering::Equal, 0, _) => Ordering::Less,
+            (Ordering::Equal, _, 0) => Ordering::Greater,
+            (Ordering::Equal, _, _) => {
+                // r/b < s/d exactly when b/r > d/s, so the next terms compare
+                // the other way around
+                (a, b, c, d) = (b, r, d, s);
+                reversed = !reversed;
+                continue;
+            }
+            (ordering, _, _) => ordering,
+        };
+        return if reversed {
+            ordering.reverse()
+        } else {
+            ordering
+        };
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Rational) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Shows integers without a denominator and anything else as `n/d`.
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::rational::Rational;
+
+    fn r(numerator: i128, denominator: i128) -> Rational {
+        Rational::new(numerator, denominator)
+    }
+
+    #[test]
+    fn test_normalisation() {
+        assert_eq!(r(2, 4), r(1, 2));
+        assert_eq!(r(3, -6), r(-1, 2));
+        assert_eq!(r(0, -5), Rational::ZERO);
+        assert_eq!(r(-3, -6).denominator(), 2);
+        assert_eq!(Rational::checked_new(1, 0), None);
+        assert_eq!(Rational::checked_new(i128::MIN, -1), None);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(r(1, 2) + r(1, 3), r(5, 6));
+        assert_eq!(r(1, 2) - r(1, 3), r(1, 6));
+        assert_eq!(r(2, 3) * r(9, 4), r(3, 2));
+        assert_eq!(r(2, 3) / r(4, 9), r(3, 2));
+        assert_eq!(-r(2, 3), r(-2, 3));
+        assert_eq!(r(2, 3).recip(), Some(r(3, 2)));
+        assert_eq!(Rational::ZERO.recip(), None);
+
+        let huge = Rational::integer(i128::MAX);
+        assert_eq!(huge.checked_add(Rational::ONE), None);
+        assert_eq!(huge.checked_mul(r(1, 2)), Some(r(i128::MAX, 2)));
+        assert_eq!(huge.checked_div(Rational::ZERO), None);
+    }
+
+    #[test]
+    fn test_rounding_and_order() {
+        assert_eq!((r(7, 2).floor(), r(7, 2).ceil()), (3, 4));
+        assert_eq!((r(-7, 2).floor(), r(-7, 2).ceil()), (-4, -3));
+        assert_eq!(r(-7, 2).fract(), r(1, 2));
+        assert_eq!(r(4, 2).to_integer(), Some(2));
+        assert_eq!(r(5, 2).to_integer(), None);
+
+        assert!(r(1, 3) < r(1, 2));
+        assert!(r(-1, 2) < r(-1, 3));
+        assert!(Rational::integer(i128::MAX) > r(i128::MAX - 1, 3));
+        assert!(r(i128::MAX, 3) < r(i128::MAX - 1, 2));
+        assert!(r(i128::MAX - 2, i128::MAX - 1) < r(i128::MAX - 1, i128::MAX));
+        assert_eq!(
+            r(i128::MAX, 7).cmp(&r(i128::MAX, 7)),
+            std::cmp::Ordering::Equal
+        );
+        assert_eq!(r(-7, 2).abs(), r(7, 2));
+        assert_eq!(r(-7, 2).to_string(), "-7/2");
+        assert_eq!(Rational::from(4u8).to_string(), "4");
+    }
+}
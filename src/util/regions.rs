@@ -0,0 +1,277 @@
+//! Splits a grid into regions of orthogonally connected, equal cells and
+//! measures them, for the garden and flood fill puzzles.
+
+use crate::util::grid::{Grid, Position};
+use std::collections::BTreeSet;
+
+/// A region of a grid, see [`Regions`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    pub label: usize,
+    /// The first cell of the region, row by row.
+    pub start: Position,
+    pub area: usize,
+    /// How many cell edges separate the region from other regions or the
+    /// outside of the grid.
+    pub perimeter: usize,
+    /// How many straight sides the outline of the region has, counting the
+    /// outlines of its holes too.
+    pub sides: usize,
+    /// The top left corner of the bounding box.
+    pub min: Position,
+    /// The bottom right corner of the bounding box, which is inclusive.
+    pub max: Position,
+    /// The labels of every region that shares an edge with this one.
+    pub neighbours: BTreeSet<usize>,
+    pub touches_border: bool,
+}
+
+/// Every region of a grid, labelled in the order their first cells appear row
+/// by row.
+#[derive(Debug, Clone)]
+pub struct Regions {
+    labels: Grid<usize>,
+    regions: Vec<Region>,
+}
+
+impl Regions {
+    /// Labels the regions of cells that are equal to each other.
+    pub fn new<T: PartialEq>(grid: &Grid<T>) -> Regions {
+        Regions::new_by(grid, |a, b| a == b)
+    }
+
+    /// Labels the regions of cells for which `same` holds between neighbours.
+    /// `same` must be symmetric.
+    pub fn new_by<T>(grid: &Grid<T>, mut same: impl FnMut(&T, &T) -> bool) -> Regions {
+        let mut labels = Grid::new(grid.width(), grid.height(), usize::MAX);
+        let mut starts = Vec::new();
+        let mut stack = Vec::new();
+        for position in grid.positions() {
+            if labels[position] != usize::MAX {
+                continue;
+            }
+
+            let label = starts.len();
+            starts.push(position);
+            labels[position] = label;
+            stack.push(position);
+            while let Some(current) = stack.pop() {
+                for next in grid.neighbours4(current) {
+                    if labels[next] == usize::MAX && same(&grid[current], &grid[next]) {
+                        labels[next] = label;
+                        stack.push(next);
+                    }
+                }
+            }
+        }
+
+        let regions = measure(&labels, &starts);
+        Regions { labels, regions }
+    }
+
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Region> {
+        self.regions.iter()
+    }
+
+    pub fn get(&self, label: usize) -> &Region {
+        &self.regions[label]
+    }
+
+    /// The region a cell belongs to.
+    pub fn at(&self, position: Position) -> &Region {
+        &self.regions[self.labels[position]]
+    }
+
+    /// The label of every cell.
+    pub fn labels(&self) -> &Grid<usize> {
+        &self.labels
+    }
+
+    /// Every cell of a region, row by row.
+    pub fn cells(&self, label: usize) -> impl Iterator<Item = Position> + '_ {
+        let Region { min, max, .. } = self.regions[label];
+        (min.1..=max.1)
+            .flat_map(move |y| (min.0..=max.0).map(move |x| (x, y)))
+            .filter(move |&position| self.labels[position] == label)
+    }
+
+    /// The holes in a region, each given as the sorted labels of the regions
+    /// that fill it. A hole is a part of the grid that can't reach the outside
+    /// without crossing the region.
+    pub fn holes(&self, label: usize) -> Vec<Vec<usize>> {
+        let mut seen = vec![false; self.regions.len()];
+        seen[label] = true;
+
+        // Everything that touches the border is connected through the outside
+        let mut stack: Vec<usize> = self
+            .regions
+            .iter()
+            .filter(|region| region.touches_border && !seen[region.label])
+            .map(|region| region.label)
+            .collect();
+        stack.iter().for_each(|&other| seen[other] = true);
+        self.fill(&mut stack, &mut seen);
+
+        let mut holes = Vec::new();
+        for other in 0..self.regions.len() {
+            if !seen[other] {
+                seen[other] = true;
+                let mut stack = vec![other];
+                let mut hole = self.fill(&mut stack, &mut seen);
+                hole.push(other);
+                hole.sort_unstable();
+                holes.push(hole);
+            }
+        }
+
+        holes
+    }
+
+    /// The labels of every region in one of the holes of a region, sorted.
+    pub fn enclosed(&self, label: usize) -> Vec<usize> {
+        let mut enclosed: Vec<usize> = self.holes(label).into_iter().flatten().collect();
+        enclosed.sort_unstable();
+        enclosed
+    }
+
+    /// Visits every region connected to the ones on the stack that hasn't been
+    /// seen yet, returning the newly seen ones.
+    fn fill(&self, stack: &mut Vec<usize>, seen: &mut [bool]) -> Vec<usize> {
+        let mut filled = Vec::new();
+        while let Some(current) = stack.pop() {
+            for &next in &self.regions[current].neighbours {
+                if !seen[next] {
+                    seen[next] = true;
+                    filled.push(next);
+                    stack.push(next);
+                }
+            }
+        }
+
+        filled
+    }
+}
+
+/// Measures every region in a single pass over the labels.
+fn measure(labels: &Grid<usize>, starts: &[Position]) -> Vec<Region> {
+    let mut regions: Vec<Region> = starts
+        .iter()
+        .enumerate()
+        .map(|(label, &start)| Region {
+            label,
+            start,
+            area: 0,
+            perimeter: 0,
+            sides: 0,
+            min: start,
+            max: start,
+            neighbours: BTreeSet::new(),
+            touches_border: false,
+        })
+        .collect();
+
+    for (position, &label) in labels.iter() {
+        let (x, y) = (position.0 as i64, position.1 as i64);
+        let inside = |dx: i64, dy: i64| labels.get_signed(x + dx, y + dy) == Some(&label);
+        let region = &mut regions[label];
+        region.area += 1;
+        region.min = (region.min.0.min(position.0), region.min.1.min(position.1));
+        region.max = (region.max.0.max(position.0), region.max.1.max(position.1));
+
+        for (dx, dy) in [(0, -1), (1, 0), (0, 1), (-1, 0)] {
+            match labels.get_signed(x + dx, y + dy) {
+                Some(&other) if other == label => continue,
+                Some(&other) => {
+                    region.neighbours.insert(other);
+                }
+                None => region.touches_border = true,
+            }
+            region.perimeter += 1;
+        }
+
+        // Every corner of the outline starts a new side. A corner of this cell
+        // is on the outline if both edges next to it are, or if neither is but
+        // the diagonal cell is outside of the region.
+        for (dx, dy) in [(1, -1), (1, 1), (-1, 1), (-1, -1)] {
+            let (horizontal, vertical) = (inside(dx, 0), inside(0, dy));
+            if (!horizontal && !vertical) || (horizontal && vertical && !inside(dx, dy)) {
+                region.sides += 1;
+            }
+        }
+    }
+
+    regions
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::grid::Grid;
+    use crate::util::regions::Regions;
+    use std::collections::BTreeSet;
+
+    fn label(input: &str) -> Regions {
+        Regions::new(&Grid::parse(input).unwrap())
+    }
+
+    #[test]
+    fn test_measurements() {
+        let regions = label("AAAA\nBBCD\nBBCC\nEEEC");
+        assert_eq!(regions.len(), 5);
+
+        let a = regions.get(0);
+        assert_eq!((a.area, a.perimeter, a.sides), (4, 10, 4));
+        assert_eq!((a.min, a.max), ((0, 0), (3, 0)));
+        assert_eq!(a.neighbours, BTreeSet::from([1, 2, 3]));
+
+        let c = regions.at((3, 3));
+        assert_eq!(c.start, (2, 1));
+        assert_eq!((c.area, c.perimeter, c.sides), (4, 10, 8));
+        assert_eq!(
+            regions.cells(c.label).collect::<Vec<_>>(),
+            [(2, 1), (2, 2), (3, 2), (3, 3)]
+        );
+        assert!(regions.iter().all(|region| region.touches_border));
+    }
+
+    #[test]
+    fn test_holes() {
+        let regions = label("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO");
+        let outer = regions.get(0);
+        assert_eq!((outer.area, outer.perimeter, outer.sides), (21, 36, 20));
+        assert_eq!(regions.holes(0), [[1], [2], [3], [4]]);
+        assert_eq!(regions.enclosed(0), [1, 2, 3, 4]);
+        assert!(!regions.get(1).touches_border);
+        assert!(regions.holes(1).is_empty());
+
+        // One hole filled by two regions, inside a region that is itself enclosed
+        let regions = label("AAAAAA\nABBBBA\nABCDBA\nABBBBA\nAAAAAA");
+        assert_eq!(regions.holes(1), [[2, 3]]);
+        assert_eq!(regions.enclosed(0), [1, 2, 3]);
+        assert_eq!(regions.holes(2), Vec::<Vec<usize>>::new());
+    }
+
+    #[test]
+    fn test_diagonal_corners() {
+        // The two B cells only touch diagonally, so they are separate regions,
+        // and the A outline turns twice where they meet
+        let regions = label("AAAA\nABAA\nAABA\nAAAA");
+        assert_eq!(regions.len(), 3);
+        assert_eq!(regions.get(0).sides, 4 + 4 + 4);
+        assert_eq!(regions.get(0).perimeter, 16 + 8);
+        assert_eq!(regions.enclosed(0), [1, 2]);
+
+        let regions = Regions::new_by(&Grid::parse("12\n34").unwrap(), |a, b| {
+            a.is_ascii_digit() == b.is_ascii_digit()
+        });
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions.get(0).sides, 4);
+    }
+}
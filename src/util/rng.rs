@@ -0,0 +1,66 @@
+use std::ops::Range;
+
+/// A small deterministic pseudo-random generator (SplitMix64) for synthetic inputs.
+/// Not suitable for anything that needs real randomness.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in the half-open range, which must not be empty.
+    pub fn range(&mut self, range: Range<usize>) -> usize {
+        assert!(range.start < range.end, "Empty range: {:?}", range);
+        range.start + (self.next_u64() % (range.end - range.start) as u64) as usize
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0..items.len())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::rng::Rng;
+
+    #[test]
+    fn test_deterministic() {
+        let a: Vec<u64> = (0..5)
+            .scan(Rng::new(42), |rng, _| Some(rng.next_u64()))
+            .collect();
+        let b: Vec<u64> = (0..5)
+            .scan(Rng::new(42), |rng, _| Some(rng.next_u64()))
+            .collect();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_range() {
+        let mut rng = Rng::new(7);
+        for _ in 0..1000 {
+            let value = rng.range(3..9);
+            assert!((3..9).contains(&value));
+        }
+    }
+
+    #[test]
+    fn test_choose_covers_all_items() {
+        let mut rng = Rng::new(1);
+        let mut seen = [false; 4];
+        for _ in 0..100 {
+            seen[*rng.choose(&[0, 1, 2, 3])] = true;
+        }
+        assert_eq!(seen, [true; 4]);
+    }
+}
@@ -1,5 +1,326 @@
+use crate::util::progress::Progress;
+use crate::util::rng::Rng;
+use crate::util::shape::Shape;
+use anyhow::{Context, Result};
+use std::any::Any;
+use std::fmt;
+use std::fs;
+use std::time::{Duration, Instant};
+
+/// Where inputs are read from unless configured otherwise.
+pub const INPUTS: &str = "./src/years";
+
+/// The answer to a single part, kept in its printed form so that variants
+/// returning different integer types can still be compared with each other.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answer(String);
+
+macro_rules! impl_answer_from {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer(value.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_answer_from!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, String, &str);
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+pub type Solver = fn(&str, &Progress) -> Answer;
+
+/// How a variant is actually run: an in-tree [`Solver`], or a call into a plugin.
+pub type Solve = Box<dyn Fn(&str, &Progress) -> Answer>;
+
+/// Parses the input without solving anything, so that malformed input is
+/// reported as an error before a solver gets to panic on it.
+pub type Parser = fn(&str) -> Result<()>;
+
+/// Produces a random input whose amount of data (lines, files, cells...) grows
+/// linearly with the given size.
+pub type Generator = fn(usize, &mut Rng) -> String;
+
+/// One named implementation of a part, e.g. a naive and an optimized one.
+pub struct Variant {
+    pub name: &'static str,
+    pub solve: Solve,
+}
+
+impl Variant {
+    pub fn run(&self, input: &str) -> (Answer, Duration) {
+        self.run_with(input, &Progress::new())
+    }
+
+    pub fn run_with(&self, input: &str, progress: &Progress) -> (Answer, Duration) {
+        let now = Instant::now();
+        let answer = (self.solve)(input, progress);
+        (answer, now.elapsed())
+    }
+
+    /// Runs the variant `iterations` times, keeping the fastest run.
+    pub fn bench(&self, input: &str, iterations: u32) -> (Answer, Duration) {
+        (0..iterations.max(1))
+            .map(|_| self.run(input))
+            .min_by_key(|(_, elapsed)| *elapsed)
+            .unwrap()
+    }
+}
+
 pub struct Runner {
+    pub year: u16,
     pub day: u8,
-    pub part1: fn() -> usize,
-    pub part2: fn() -> usize,
-}
\ No newline at end of file
+    pub part1: Vec<Variant>,
+    pub part2: Vec<Variant>,
+    pub budgets: [Option<Duration>; 2],
+    pub shape: Option<Shape>,
+    pub parser: Option<Parser>,
+    pub generator: Option<Generator>,
+}
+
+impl Runner {
+    pub fn new(year: u16, day: u8) -> Runner {
+        Runner {
+            year,
+            day,
+            part1: Vec::new(),
+            part2: Vec::new(),
+            budgets: [None, None],
+            shape: None,
+            parser: None,
+            generator: None,
+        }
+    }
+
+    /// Registers a variant for part 1. The first registered variant is the default.
+    pub fn part1(mut self, name: &'static str, solve: Solver) -> Runner {
+        self.part1.push(Variant {
+            name,
+            solve: Box::new(solve),
+        });
+        self
+    }
+
+    /// Registers a variant for part 2. The first registered variant is the default.
+    pub fn part2(mut self, name: &'static str, solve: Solver) -> Runner {
+        self.part2.push(Variant {
+            name,
+            solve: Box::new(solve),
+        });
+        self
+    }
+
+    /// Declares how long a part is allowed to take, as checked by the budget command.
+    pub fn budget(mut self, part: u8, budget: Duration) -> Runner {
+        assert!(
+            matches!(part, 1 | 2),
+            "Budget for day {} must be for part 1 or 2, not {}",
+            self.day,
+            part
+        );
+        self.budgets[part as usize - 1] = Some(budget);
+        self
+    }
+
+    /// Declares what the input should look like, see [`Runner::check_input`].
+    pub fn shape(mut self, shape: Shape) -> Runner {
+        self.shape = Some(shape);
+        self
+    }
+
+    /// Registers the day's parser, see [`Runner::check_input`].
+    pub fn parser(mut self, parser: Parser) -> Runner {
+        self.parser = Some(parser);
+        self
+    }
+
+    /// Registers a generator for synthetic inputs, used by the scale command.
+    pub fn generator(mut self, generator: Generator) -> Runner {
+        self.generator = Some(generator);
+        self
+    }
+
+    pub fn parts(&self) -> [(u8, &[Variant]); 2] {
+        [(1, &self.part1), (2, &self.part2)]
+    }
+
+    /// Looks up a variant of a part by name, or the default one if no name is given.
+    pub fn variant(&self, part: u8, name: Option<&str>) -> Option<&Variant> {
+        let variants = match part {
+            1 => &self.part1,
+            2 => &self.part2,
+            _ => return None,
+        };
+
+        match name {
+            Some(name) => variants.iter().find(|variant| variant.name == name),
+            None => variants.first(),
+        }
+    }
+
+    /// Picks the named variant for every part that has one, falling back to the
+    /// default variant for parts that don't.
+    pub fn select(&self, name: Option<&str>) -> Vec<(u8, &Variant)> {
+        if let Some(name) = name {
+            if self
+                .parts()
+                .iter()
+                .all(|(part, _)| self.variant(*part, Some(name)).is_none())
+            {
+                panic!("Unknown variant for day {}: {}", self.day, name);
+            }
+        }
+
+        self.parts()
+            .iter()
+            .filter_map(|(part, _)| {
+                self.variant(*part, name)
+                    .or_else(|| self.variant(*part, None))
+                    .map(|variant| (*part, variant))
+            })
+            .collect()
+    }
+
+    pub fn input_path_in(&self, inputs: &str) -> String {
+        format!("{}/y{}/day{:02}.txt", inputs, self.year, self.day)
+    }
+
+    pub fn read_input_in(&self, inputs: &str) -> String {
+        self.read_input_from(&self.input_path_in(inputs))
+            .expect("Should have been able to read the file")
+    }
+
+    /// Reads an input file, dropping the trailing newline puzzle inputs are usually saved with.
+    pub fn read_input_from(&self, path: &str) -> Result<String> {
+        let input = fs::read_to_string(path).with_context(|| format!("Could not read {}", path))?;
+        Ok(input.trim_end_matches(['\n', '\r']).to_string())
+    }
+
+    /// Checks the input against the declared shape and runs the parser, if any.
+    pub fn check_input(&self, input: &str) -> Result<()> {
+        let context = || {
+            format!(
+                "This does not look like {} day {} input",
+                self.year, self.day
+            )
+        };
+
+        if let Some(shape) = &self.shape {
+            shape.check(input).with_context(context)?;
+        }
+        if let Some(parser) = self.parser {
+            parser(input).with_context(context)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The message a solver panicked with.
+pub fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::runner::{Answer, Runner, INPUTS};
+    use crate::util::shape::Shape;
+    use std::time::Duration;
+
+    fn runner() -> Runner {
+        Runner::new(2024, 1)
+            .part1("slow", |input, _| input.len().into())
+            .part1("fast", |input, _| (input.len() as i64).into())
+            .part2("only", |_, _| "answer".into())
+    }
+
+    #[test]
+    fn test_variant_lookup() {
+        let runner = runner();
+        assert_eq!(runner.variant(1, None).unwrap().name, "slow");
+        assert_eq!(runner.variant(1, Some("fast")).unwrap().name, "fast");
+        assert!(runner.variant(2, Some("fast")).is_none());
+        assert!(runner.variant(3, None).is_none());
+    }
+
+    #[test]
+    fn test_select_falls_back_to_default() {
+        let runner = runner();
+        let selected: Vec<_> = runner
+            .select(Some("fast"))
+            .iter()
+            .map(|(part, variant)| (*part, variant.name))
+            .collect();
+        assert_eq!(selected, vec![(1, "fast"), (2, "only")]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown variant for day 1: missing")]
+    fn test_select_unknown_variant() {
+        runner().select(Some("missing"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Budget for day 1 must be for part 1 or 2, not 0")]
+    fn test_budget_for_unknown_part() {
+        runner().budget(0, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_answers_compare_across_types() {
+        let runner = runner();
+        let (slow, _) = runner.part1[0].run("abc");
+        let (fast, _) = runner.part1[1].run("abc");
+        assert_eq!(slow, fast);
+        assert_eq!(slow, Answer::from(3u8));
+    }
+
+    #[test]
+    fn test_check_input() {
+        let runner = runner().shape(Shape::Grid("[0-9]"));
+        assert!(runner.check_input("0123\n4567").is_ok());
+
+        let error = runner.check_input("0123\n45x7").unwrap_err();
+        assert_eq!(
+            format!("{:#}", error),
+            r#"This does not look like 2024 day 1 input: line 2 ("45x7") contains cells outside of [0-9]"#
+        );
+    }
+
+    #[test]
+    fn test_check_input_runs_parser() {
+        let runner = runner().parser(|input| {
+            input.parse::<u8>()?;
+            Ok(())
+        });
+        assert!(runner.check_input("42").is_ok());
+
+        let error = runner.check_input("420").unwrap_err();
+        assert_eq!(
+            format!("{:#}", error),
+            "This does not look like 2024 day 1 input: number too large to fit in target type"
+        );
+    }
+
+    #[test]
+    fn test_input_path() {
+        assert_eq!(
+            runner().input_path_in(INPUTS),
+            "./src/years/y2024/day01.txt"
+        );
+        assert_eq!(runner().input_path_in("inputs"), "inputs/y2024/day01.txt");
+    }
+}
@@ -0,0 +1,401 @@
+//! Searches over graphs that are only known through a closure listing the
+//! neighbours of a node, so that a grid, a state machine or an explicit graph
+//! can all be searched the same way.
+
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// A node of a searched graph.
+pub trait Node: Clone + Eq + Hash {}
+
+impl<T: Clone + Eq + Hash> Node for T {}
+
+/// The cost of an edge, where the default value is a cost of zero.
+pub trait Cost: Copy + Ord + Add<Output = Self> + Default {}
+
+impl<T: Copy + Ord + Add<Output = T> + Default> Cost for T {}
+
+/// Every node reachable from the start, including the start itself.
+pub fn reachable<N: Node, I>(start: N, mut neighbours: impl FnMut(&N) -> I) -> HashSet<N>
+where
+    I: IntoIterator<Item = N>,
+{
+    let mut seen = HashSet::from([start.clone()]);
+    let mut stack = vec![start];
+    while let Some(node) = stack.pop() {
+        for next in neighbours(&node) {
+            if seen.insert(next.clone()) {
+                stack.push(next);
+            }
+        }
+    }
+
+    seen
+}
+
+/// Searches breadth first for a goal, returning a path with the fewest edges
+/// from the start to the first goal found.
+pub fn bfs<N: Node, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut goal: impl FnMut(&N) -> bool,
+) -> Option<Vec<N>>
+where
+    I: IntoIterator<Item = N>,
+{
+    let mut parents = HashMap::new();
+    let mut seen = HashSet::from([start.clone()]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        if goal(&node) {
+            return Some(path(&parents, node));
+        }
+        for next in neighbours(&node) {
+            if seen.insert(next.clone()) {
+                parents.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+/// Searches depth first for a goal, returning the path to the first goal found,
+/// which isn't necessarily the shortest one.
+pub fn dfs<N: Node, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut goal: impl FnMut(&N) -> bool,
+) -> Option<Vec<N>>
+where
+    I: IntoIterator<Item = N>,
+{
+    let mut parents = HashMap::new();
+    let mut seen = HashSet::new();
+    let mut stack = vec![(start, None)];
+    while let Some((node, parent)) = stack.pop() {
+        if !seen.insert(node.clone()) {
+            continue;
+        }
+        if let Some(parent) = parent {
+            parents.insert(node.clone(), parent);
+        }
+        if goal(&node) {
+            return Some(path(&parents, node));
+        }
+        for next in neighbours(&node) {
+            if !seen.contains(&next) {
+                stack.push((next, Some(node.clone())));
+            }
+        }
+    }
+
+    None
+}
+
+/// Finds the cheapest path from the start to a goal, where `neighbours` lists
+/// every neighbour with the non-negative cost of getting there. Returns the
+/// cost along with the path.
+pub fn dijkstra<N: Node, C: Cost, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    goal: impl FnMut(&N) -> bool,
+) -> Option<(C, Vec<N>)>
+where
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, neighbours, |_| C::default(), goal)
+}
+
+/// Like [`dijkstra`], but guided by a heuristic that estimates the remaining
+/// cost from a node to the closest goal. The path is only guaranteed to be the
+/// cheapest if the heuristic never overestimates.
+pub fn astar<N: Node, C: Cost, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut goal: impl FnMut(&N) -> bool,
+) -> Option<(C, Vec<N>)>
+where
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut parents = HashMap::new();
+    let mut costs = HashMap::from([(start.clone(), C::default())]);
+    let mut queue = BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+    let mut nodes = vec![start];
+    while let Some(Reverse((_, cost, index))) = queue.pop() {
+        let node = nodes[index].clone();
+        if costs[&node] < cost {
+            continue;
+        }
+        if goal(&node) {
+            return Some((cost, path(&parents, node)));
+        }
+
+        for (next, step) in neighbours(&node) {
+            let next_cost = cost + step;
+            if costs.get(&next).is_some_and(|&known| known <= next_cost) {
+                continue;
+            }
+            costs.insert(next.clone(), next_cost);
+            parents.insert(next.clone(), node.clone());
+            queue.push(Reverse((
+                next_cost + heuristic(&next),
+                next_cost,
+                nodes.len(),
+            )));
+            nodes.push(next);
+        }
+    }
+
+    None
+}
+
+/// Finds every cheapest path from the start to every reachable node, where
+/// `neighbours` lists every neighbour with the cost of getting there. Costs of
+/// zero are allowed, but [`ShortestPaths::path_counts`] needs positive costs.
+/// The start never gets predecessors, so edges back into it are ignored.
+pub fn shortest_paths<N: Node, C: Cost, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+) -> ShortestPaths<N, C>
+where
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut costs = HashMap::from([(start.clone(), C::default())]);
+    let mut predecessors: HashMap<N, Vec<N>> = HashMap::new();
+    let mut order = Vec::new();
+    let mut queue = BinaryHeap::from([Reverse((C::default(), 0))]);
+    let mut nodes = vec![start.clone()];
+    while let Some(Reverse((cost, index))) = queue.pop() {
+        let node = nodes[index].clone();
+        if costs[&node] < cost {
+            continue;
+        }
+        order.push(node.clone());
+
+        for (next, step) in neighbours(&node) {
+            if next == start {
+                continue;
+            }
+
+            let next_cost = cost + step;
+            match costs.entry(next.clone()) {
+                Entry::Occupied(known) if *known.get() < next_cost => continue,
+                Entry::Occupied(known) if *known.get() == next_cost => {
+                    predecessors.entry(next).or_default().push(node.clone());
+                    continue;
+                }
+                Entry::Occupied(mut known) => {
+                    known.insert(next_cost);
+                }
+                Entry::Vacant(unknown) => {
+                    unknown.insert(next_cost);
+                }
+            }
+            predecessors.insert(next.clone(), vec![node.clone()]);
+            queue.push(Reverse((next_cost, nodes.len())));
+            nodes.push(next);
+        }
+    }
+
+    ShortestPaths {
+        start,
+        costs,
+        predecessors,
+        order,
+    }
+}
+
+/// Every cheapest path from a start node, see [`shortest_paths`].
+#[derive(Debug, Clone)]
+pub struct ShortestPaths<N, C> {
+    start: N,
+    costs: HashMap<N, C>,
+    predecessors: HashMap<N, Vec<N>>,
+    /// Every reached node, ordered by cost.
+    order: Vec<N>,
+}
+
+impl<N: Node, C: Cost> ShortestPaths<N, C> {
+    /// The cost of the cheapest path to a node, if it can be reached.
+    pub fn cost(&self, node: &N) -> Option<C> {
+        self.costs.get(node).copied()
+    }
+
+    /// Every reached node, ordered by cost.
+    pub fn nodes(&self) -> &[N] {
+        &self.order
+    }
+
+    /// The nodes a cheapest path to the node may come from.
+    pub fn predecessors(&self, node: &N) -> &[N] {
+        self.predecessors.get(node).map_or(&[], Vec::as_slice)
+    }
+
+    /// One of the cheapest paths to a node, if it can be reached.
+    pub fn path(&self, node: &N) -> Option<Vec<N>> {
+        self.costs.get(node)?;
+        let mut path = vec![node.clone()];
+        while let Some(previous) = self.predecessors(path.last().unwrap()).first() {
+            path.push(previous.clone());
+        }
+
+        path.reverse();
+        Some(path)
+    }
+
+    /// How many different cheapest paths lead to every reached node.
+    pub fn path_counts(&self) -> HashMap<N, u64> {
+        let mut counts = HashMap::from([(self.start.clone(), 1)]);
+        for node in &self.order[1..] {
+            let count = self
+                .predecessors(node)
+                .iter()
+                .map(|previous| counts[previous])
+                .sum();
+            counts.insert(node.clone(), count);
+        }
+
+        counts
+    }
+
+    /// Every node that lies on at least one of the cheapest paths to the node,
+    /// including the start and the node itself.
+    pub fn on_paths_to(&self, node: &N) -> HashSet<N> {
+        let mut on_paths = HashSet::new();
+        if self.costs.contains_key(node) {
+            on_paths.insert(node.clone());
+        }
+
+        let mut stack = vec![node.clone()];
+        while let Some(node) = stack.pop() {
+            for previous in self.predecessors(&node) {
+                if on_paths.insert(previous.clone()) {
+                    stack.push(previous.clone());
+                }
+            }
+        }
+
+        on_paths
+    }
+}
+
+/// Follows the parents back from a node to the start.
+fn path<N: Node>(parents: &HashMap<N, N>, node: N) -> Vec<N> {
+    let mut path = vec![node];
+    while let Some(parent) = parents.get(path.last().unwrap()) {
+        path.push(parent.clone());
+    }
+
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::grid::{Grid, Position};
+    use crate::util::search::{astar, bfs, dfs, dijkstra, reachable, shortest_paths};
+
+    // Two equally long ways around the wall to E, and a dead end below it
+    const MAZE: &str = "S...\n.##.\n...E\n##.#\n#..#";
+
+    fn maze() -> Grid<char> {
+        Grid::parse(MAZE).unwrap()
+    }
+
+    fn open(grid: &Grid<char>) -> impl FnMut(&Position) -> Vec<Position> + '_ {
+        |&position| {
+            grid.neighbours4(position)
+                .filter(|&next| grid[next] != '#')
+                .collect()
+        }
+    }
+
+    fn weighted(grid: &Grid<char>) -> impl FnMut(&Position) -> Vec<(Position, u32)> + '_ {
+        let mut open = open(grid);
+        move |position| open(position).into_iter().map(|next| (next, 1)).collect()
+    }
+
+    #[test]
+    fn test_reachable() {
+        let grid = maze();
+        assert_eq!(reachable((0, 0), open(&grid)).len(), 13);
+        assert_eq!(reachable((1, 1), |_| Vec::new()).len(), 1);
+    }
+
+    #[test]
+    fn test_bfs_and_dfs() {
+        let grid = maze();
+        let path = bfs((0, 0), open(&grid), |&position| grid[position] == 'E').unwrap();
+        assert_eq!(path.len(), 6);
+        assert_eq!((path[0], path[5]), ((0, 0), (3, 2)));
+
+        let path = dfs((0, 0), open(&grid), |&position| position == (1, 4)).unwrap();
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(1, 4)));
+        assert!(path
+            .windows(2)
+            .all(|step| open(&grid)(&step[0]).contains(&step[1])));
+
+        assert_eq!(bfs((0, 0), open(&grid), |_| false), None);
+        assert_eq!(
+            dfs((0, 0), open(&grid), |&position| position == (0, 4)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar() {
+        // Going right is cheap and going down is expensive
+        let neighbours = |&(x, y): &(u32, u32)| [((x + 1, y), 1), ((x, y + 1), 10)];
+        let goal = |&node: &(u32, u32)| node == (3, 2);
+
+        let (cost, path) = dijkstra((0, 0), neighbours, goal).unwrap();
+        assert_eq!(cost, 23);
+        assert_eq!(path.len(), 6);
+
+        let heuristic = |&(x, y): &(u32, u32)| 3u32.saturating_sub(x) + 10 * 2u32.saturating_sub(y);
+        assert_eq!(astar((0, 0), neighbours, heuristic, goal).unwrap().0, 23);
+    }
+
+    #[test]
+    fn test_shortest_paths() {
+        let grid = maze();
+        let paths = shortest_paths((0, 0), weighted(&grid));
+        assert_eq!(paths.cost(&(3, 2)), Some(5));
+        assert_eq!(paths.cost(&(1, 4)), Some(7));
+        assert_eq!(paths.cost(&(0, 4)), None);
+        assert_eq!(paths.nodes()[0], (0, 0));
+        assert_eq!(paths.path(&(3, 2)).unwrap().len(), 6);
+
+        let counts = paths.path_counts();
+        assert_eq!(counts[&(3, 2)], 2);
+        assert_eq!(counts[&(1, 4)], 1);
+        assert_eq!(paths.predecessors(&(3, 2)).len(), 2);
+        assert_eq!(paths.predecessors(&(2, 2)), [(1, 2)].as_slice());
+
+        let on_paths = paths.on_paths_to(&(3, 2));
+        assert_eq!(on_paths.len(), 10);
+        assert!(!on_paths.contains(&(2, 4)));
+    }
+
+    #[test]
+    fn test_shortest_paths_with_free_edges() {
+        // Stepping back to the start costs nothing, which must not give the
+        // start a predecessor
+        let paths = shortest_paths(0u8, |&node| match node {
+            0 => vec![(1, 0u32), (2, 3)],
+            1 => vec![(0, 0), (2, 1)],
+            _ => vec![],
+        });
+        assert!(paths.predecessors(&0).is_empty());
+        assert_eq!(paths.path(&0), Some(vec![0]));
+        assert_eq!(paths.path(&2), Some(vec![0, 1, 2]));
+        assert_eq!(paths.cost(&2), Some(1));
+    }
+}
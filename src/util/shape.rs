@@ -0,0 +1,205 @@
+use anyhow::{bail, Result};
+use regex::Regex;
+
+/// A rough description of what a day's input looks like, checked before solving
+/// so that feeding a day the wrong file fails with a clear message.
+pub enum Shape {
+    /// A single line matching the pattern.
+    Line(&'static str),
+    /// One or more lines, each matching the pattern.
+    Lines(&'static str),
+    /// A rectangular grid of at least two rows where every cell matches the
+    /// character class, e.g. `[0-9]`.
+    Grid(&'static str),
+    /// Blank-line separated sections, each with its own shape.
+    Sections(Vec<Shape>),
+    /// Any number of blank-line separated blocks sharing the same shape.
+    Blocks(Box<Shape>),
+}
+
+impl Shape {
+    pub fn check(&self, input: &str) -> Result<()> {
+        self.check_from(input, 1)
+    }
+
+    fn check_from(&self, input: &str, first_line: usize) -> Result<()> {
+        if input.trim().is_empty() {
+            bail!("input is empty");
+        }
+
+        match self {
+            Shape::Line(pattern) => {
+                let lines = input.lines().count();
+                if lines != 1 {
+                    bail!("expected a single line but found {}", lines);
+                }
+                check_lines(pattern, input, first_line)
+            }
+            Shape::Lines(pattern) => check_lines(pattern, input, first_line),
+            Shape::Grid(class) => check_grid(class, input, first_line),
+            Shape::Sections(shapes) => {
+                let blocks = blocks(input, first_line);
+                if blocks.len() != shapes.len() {
+                    bail!(
+                        "expected {} sections separated by blank lines but found {}",
+                        shapes.len(),
+                        blocks.len()
+                    );
+                }
+                shapes
+                    .iter()
+                    .zip(blocks)
+                    .try_for_each(|(shape, (line, block))| shape.check_from(&block, line))
+            }
+            Shape::Blocks(shape) => blocks(input, first_line)
+                .into_iter()
+                .try_for_each(|(line, block)| shape.check_from(&block, line)),
+        }
+    }
+}
+
+fn check_lines(pattern: &str, input: &str, first_line: usize) -> Result<()> {
+    let regex = Regex::new(&format!("^(?:{})$", pattern)).unwrap();
+
+    for (i, line) in input.lines().enumerate() {
+        if !regex.is_match(line) {
+            bail!(
+                "line {} ({:?}) does not match `{}`",
+                first_line + i,
+                preview(line),
+                pattern
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn check_grid(class: &str, input: &str, first_line: usize) -> Result<()> {
+    let regex = Regex::new(&format!("^(?:{})+$", class)).unwrap();
+    let width = input.lines().next().unwrap_or_default().chars().count();
+
+    if input.lines().count() < 2 {
+        bail!("expected a grid but found a single line");
+    }
+
+    for (i, line) in input.lines().enumerate() {
+        if !regex.is_match(line) {
+            bail!(
+                "line {} ({:?}) contains cells outside of {}",
+                first_line + i,
+                preview(line),
+                class
+            );
+        }
+
+        let length = line.chars().count();
+        if length != width {
+            bail!(
+                "line {} is {} cells wide but the grid is {} cells wide",
+                first_line + i,
+                length,
+                width
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Splits the input on blank lines, keeping the line number each block starts at.
+fn blocks(input: &str, first_line: usize) -> Vec<(usize, String)> {
+    let mut blocks: Vec<(usize, String)> = Vec::new();
+    let mut current: Option<(usize, Vec<&str>)> = None;
+
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            if let Some((start, lines)) = current.take() {
+                blocks.push((start, lines.join("\n")));
+            }
+        } else {
+            current
+                .get_or_insert_with(|| (first_line + i, Vec::new()))
+                .1
+                .push(line);
+        }
+    }
+
+    if let Some((start, lines)) = current {
+        blocks.push((start, lines.join("\n")));
+    }
+
+    blocks
+}
+
+fn preview(line: &str) -> String {
+    match line.char_indices().nth(20) {
+        Some((i, _)) => format!("{}...", &line[..i]),
+        None => line.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::shape::Shape;
+
+    #[test]
+    fn test_line() {
+        assert!(Shape::Line("[0-9]+").check("2333133121414131402").is_ok());
+        assert!(Shape::Line("[0-9]+").check("12\n34").is_err());
+    }
+
+    #[test]
+    fn test_lines() {
+        let shape = Shape::Lines(r"\d+ +\d+");
+        assert!(shape.check("3   4\n4   3").is_ok());
+
+        let error = shape.check("3   4\n4,3").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            r#"line 2 ("4,3") does not match `\d+ +\d+`"#
+        );
+    }
+
+    #[test]
+    fn test_grid() {
+        let shape = Shape::Grid("[0-9]");
+        assert!(shape.check("012\n345").is_ok());
+        assert!(shape.check("012\n3a5").is_err());
+        assert!(shape.check("2333133121414131402").is_err());
+
+        let error = shape.check("012\n34").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2 is 2 cells wide but the grid is 3 cells wide"
+        );
+    }
+
+    #[test]
+    fn test_sections() {
+        let shape = Shape::Sections(vec![Shape::Lines(r"\d+\|\d+"), Shape::Lines(r"\d+(,\d+)*")]);
+        assert!(shape.check("47|53\n97|13\n\n75,47,61").is_ok());
+        assert!(shape.check("47|53\n97|13").is_err());
+
+        let error = shape.check("47|53\n\n75,47\n75|47").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            r#"line 4 ("75|47") does not match `\d+(,\d+)*`"#
+        );
+    }
+
+    #[test]
+    fn test_blocks() {
+        let shape = Shape::Blocks(Box::new(Shape::Lines("[ab]")));
+        assert!(shape.check("a\nb\n\nb\n\na").is_ok());
+        assert!(shape.check("a\n\nc").is_err());
+    }
+
+    #[test]
+    fn test_empty_input() {
+        assert_eq!(
+            Shape::Lines(".*").check("\n").unwrap_err().to_string(),
+            "input is empty"
+        );
+    }
+}
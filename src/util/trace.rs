@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::Mutex;
+
+/// Lightweight tracing for solvers. Events and counters are dropped after a
+/// single relaxed atomic load unless enabled with `--trace` or `--explain`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Off,
+    Info,
+    Debug,
+    Trace,
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Off as u8);
+static COUNTING: AtomicBool = AtomicBool::new(false);
+static COUNTERS: Mutex<BTreeMap<&'static str, u64>> = Mutex::new(BTreeMap::new());
+
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn set_counting(counting: bool) {
+    COUNTING.store(counting, Ordering::Relaxed);
+}
+
+pub fn enabled(level: Level) -> bool {
+    level != Level::Off && LEVEL.load(Ordering::Relaxed) >= level as u8
+}
+
+pub fn emit(level: Level, message: fmt::Arguments) {
+    eprintln!("[{}] {}", level, message);
+}
+
+/// Adds `amount` to the named counter, e.g. iterations or states explored.
+pub fn count(name: &'static str, amount: u64) {
+    if COUNTING.load(Ordering::Relaxed) {
+        *COUNTERS.lock().unwrap().entry(name).or_default() += amount;
+    }
+}
+
+/// Returns the counters collected so far and resets them.
+pub fn take_counters() -> Vec<(&'static str, u64)> {
+    let mut counters = COUNTERS.lock().unwrap();
+    let result = counters
+        .iter()
+        .map(|(name, value)| (*name, *value))
+        .collect();
+    counters.clear();
+    result
+}
+
+/// Emits an event at the given level, formatting the message only if the level is enabled.
+#[macro_export]
+macro_rules! event {
+    ($level:expr, $($arg:tt)*) => {
+        if $crate::util::trace::enabled($level) {
+            $crate::util::trace::emit($level, format_args!($($arg)*));
+        }
+    };
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Level::Off => "off",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(Level::Off),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(format!("Unknown trace level: {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::trace::Level;
+
+    #[test]
+    fn test_levels_are_ordered() {
+        assert!(Level::Trace > Level::Debug);
+        assert!(Level::Debug > Level::Info);
+        assert!(Level::Info > Level::Off);
+    }
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!("debug".parse::<Level>(), Ok(Level::Debug));
+        assert!("loud".parse::<Level>().is_err());
+    }
+}
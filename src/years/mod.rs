@@ -1,2 +1,105 @@
+use crate::util::runner::Runner;
+use std::time::Duration;
+
 pub mod y2024;
-pub mod y2015;
\ No newline at end of file
+pub mod y2015;
+
+/// Every year with solutions.
+pub const YEARS: [u16; 2] = [2015, 2024];
+
+pub fn runners(year: u16) -> Vec<Runner> {
+    match year {
+        2015 => y2015::runners(),
+        2024 => y2024::runners(),
+        _ => panic!("Unknown year: {}", year),
+    }
+}
+
+pub fn get_runner(year: u16, day: u8) -> Runner {
+    runners(year)
+        .into_iter()
+        .find(|runner| runner.day == day)
+        .unwrap_or_else(|| panic!("Unknown day: {}", day))
+}
+
+/// The longest all parts of a year are allowed to take together.
+pub fn time_limit(year: u16) -> Option<Duration> {
+    match year {
+        2015 => Some(y2015::TIME_LIMIT),
+        2024 => Some(y2024::TIME_LIMIT),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::fuzz::{mutate, random, sample, survives};
+    use crate::util::rng::Rng;
+    use crate::util::runner::INPUTS;
+    use crate::years::{get_runner, runners};
+    use std::time::Duration;
+
+    #[test]
+    fn test_inputs_match_their_shape() {
+        for runner in runners(2015).into_iter().chain(runners(2024)) {
+            let input = runner.read_input_in(INPUTS);
+            if let Err(err) = runner.check_input(&input) {
+                panic!("{:#}", err);
+            }
+        }
+    }
+
+    #[test]
+    fn test_wrong_input_is_rejected() {
+        let day09 = get_runner(2024, 9);
+        let day10 = get_runner(2024, 10);
+
+        let error = day10.check_input(&day09.read_input_in(INPUTS)).unwrap_err();
+        assert!(format!("{:#}", error).starts_with("This does not look like 2024 day 10 input"));
+    }
+
+    #[test]
+    fn test_generated_inputs_match_their_shape() {
+        for runner in runners(2015).into_iter().chain(runners(2024)) {
+            let Some(generator) = runner.generator else {
+                continue;
+            };
+
+            for seed in 0..10 {
+                let input = generator(50, &mut Rng::new(seed));
+                if let Err(err) = runner.check_input(&input) {
+                    panic!("{:#}", err);
+                }
+                for (_, variant) in runner.select(None) {
+                    variant.run(&input);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parsers_survive_malformed_input() {
+        for runner in runners(2015).into_iter().chain(runners(2024)) {
+            let Some(parser) = runner.parser else {
+                continue;
+            };
+            let input = sample(&runner.read_input_in(INPUTS));
+
+            for seed in 0..200 {
+                let mut rng = Rng::new(seed);
+                for case in [mutate(&input, &mut rng), random(&input, &mut rng)] {
+                    if let Err(message) = survives(parser, &case, Duration::from_secs(5)) {
+                        panic!(
+                            "{} day {} parser {} for seed {} on {:?}",
+                            runner.year,
+                            runner.day,
+                            message,
+                            seed,
+                            case.chars().take(200).collect::<String>()
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,13 +1,11 @@
-use std::fs;
-
-pub fn solver() {
-    println!("Day 1:");
-
-    let input = fs::read_to_string("./src/years/y2015/day01.txt")
-        .expect("Should have been able to read the file");
-
-    println!("Part 1: {}", solve_part_1(input.as_str()));
-    println!("Part 2: {}", solve_part_2(input.as_str()));
+use crate::util::runner::Runner;
+use crate::util::shape::Shape;
+
+pub fn runner() -> Runner {
+    Runner::new(2015, 1)
+        .shape(Shape::Line("[()]+"))
+        .part1("default", |input, _| solve_part_1(input).into())
+        .part2("default", |input, _| solve_part_2(input).into())
 }
 
 fn solve_part_1(input: &str) -> isize {
@@ -19,7 +17,7 @@ fn solve_part_2(input: &str) -> usize {
     for (i, char) in input.chars().enumerate() {
         floor += if char == '(' { 1 } else { -1 };
         if floor < 0 {
-            return i
+            return i + 1
         }
     }
     0
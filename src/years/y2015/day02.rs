@@ -1,30 +1,33 @@
-use std::fs;
+use crate::util::runner::Runner;
+use crate::util::shape::Shape;
+use anyhow::{bail, Result};
 
-pub fn solver() {
-    println!("Day 2:");
-
-    let input = fs::read_to_string("./src/years/y2015/day02.txt")
-        .expect("Should have been able to read the file");
-
-    println!("Part 1: {}", solve_part_1(input.as_str()));
-    println!("Part 2: {}", solve_part_2(input.as_str()));
+pub fn runner() -> Runner {
+    Runner::new(2015, 2)
+        .shape(Shape::Lines(r"\d+x\d+x\d+"))
+        .parser(|input| parse_input(input).map(drop))
+        .part1("default", |input, _| solve_part_1(input).into())
+        .part2("default", |input, _| solve_part_2(input).into())
 }
 
 fn solve_part_1(input: &str) -> usize {
-    parse_input(input).iter().fold(0, |sum, gift| sum + gift.area() + gift.smallest_side())
+    parse_input(input).unwrap().iter().fold(0, |sum, gift| sum + gift.area() + gift.smallest_side())
 }
 
 fn solve_part_2(input: &str) -> usize {
-    parse_input(input).iter().fold(0, |sum, gift| sum + gift.length_of_ribbon())
+    parse_input(input).unwrap().iter().fold(0, |sum, gift| sum + gift.length_of_ribbon())
 }
 
-fn parse_input(input: &str) -> Vec<GiftBox> {
+fn parse_input(input: &str) -> Result<Vec<GiftBox>> {
     input.split("\n").map(|d| {
-        let components: Vec<usize> = d.split('x').map(|d| d.parse().unwrap()).collect();
-        GiftBox {
-            length: components[0],
-            width: components[1],
-            height: components[2],
+        let components = d.split('x').map(|d| d.parse()).collect::<Result<Vec<u16>, _>>()?;
+        match components[..] {
+            [length, width, height] => Ok(GiftBox {
+                length: length as usize,
+                width: width as usize,
+                height: height as usize,
+            }),
+            _ => bail!("expected three dimensions but found {:?}", d),
         }
     }).collect()
 }
@@ -41,11 +44,11 @@ impl GiftBox {
     }
 
     fn smallest_side(&self) -> usize {
-        *vec![self.length * self.width, self.width * self.height, self.height * self.length].iter().min().unwrap()
+        *[self.length * self.width, self.width * self.height, self.height * self.length].iter().min().unwrap()
     }
 
     fn smallest_perimeter(&self) -> usize {
-        *vec![self.length + self.width, self.width + self.height, self.height + self.length].iter().min().unwrap() * 2
+        *[self.length + self.width, self.width + self.height, self.height + self.length].iter().min().unwrap() * 2
     }
 
     fn length_of_ribbon(&self) -> usize {
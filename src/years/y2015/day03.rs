@@ -1,127 +1,66 @@
-use std::collections::HashMap;
-use std::convert::Infallible;
-use std::{fs, ops};
-use std::str::FromStr;
+use crate::util::counter::Counter;
+use crate::util::geometry::{Direction4, Point};
+use crate::util::runner::Runner;
+use crate::util::shape::Shape;
+use anyhow::{anyhow, Result};
+
+pub fn runner() -> Runner {
+    Runner::new(2015, 3)
+        .shape(Shape::Line("[<>^v]+"))
+        .parser(|input| parse_input(input).map(drop))
+        .part1("default", |input, _| solve_part_1(input).into())
+        .part2("default", |input, _| solve_part_2(input).into())
+}
 
-pub fn solver() {
-    println!("Day 3:");
+fn solve_part_1(input: &str) -> usize {
+    let mut houses = Counter::new();
+    let mut current_position = Position::ORIGIN;
 
-    let input = fs::read_to_string("./src/years/y2015/day03.txt")
-        .expect("Should have been able to read the file");
+    houses.insert(current_position);
 
-    println!("Part 1: {}", solve_part_1(input.as_str()));
-    println!("Part 2: {}", solve_part_2(input.as_str()));
-}
+    for direction in parse_input(input).unwrap() {
+        current_position += direction;
 
-fn solve_part_1(input: &str) -> usize {
-    let mut houses: HashMap<Position, usize> = HashMap::new();
-    let mut current_position = Position { x: 0, y: 0 };
-
-    houses.insert(current_position.clone(), 1);
-
-    for direction in input.chars() {
-        let direction = Direction::from_str(&direction.to_string()).unwrap();
-        current_position += direction.value();
-
-        match houses.get(&current_position) {
-            Some(entry) => {
-                houses.insert(current_position.clone(), entry + 1);
-            },
-            None => {
-                houses.insert(current_position.clone(), 1);
-            }
-        }
+        houses.insert(current_position);
     }
 
     houses.len()
 }
 
 fn solve_part_2(input: &str) -> usize {
-    let mut houses: HashMap<Position, usize> = HashMap::new();
-    let mut current_position = Position { x: 0, y: 0 };
+    let mut houses = Counter::new();
+    let mut current_position = Position::ORIGIN;
 
-    houses.insert(current_position.clone(), 1);
+    houses.insert(current_position);
 
-    let binding = input.chars().collect::<Vec<_>>();
-    let (a, b): (Vec<char>, Vec<char>) = binding.chunks(2).map(|c| (c[0], c[1])).unzip();
+    let directions = parse_input(input).unwrap();
+    let a = directions.iter().step_by(2);
+    let b = directions.iter().skip(1).step_by(2);
 
     for direction in a {
-        let direction = Direction::from_str(&direction.to_string()).unwrap();
-        current_position += direction.value();
-
-        match houses.get(&current_position) {
-            Some(entry) => {
-                houses.insert(current_position.clone(), entry + 1);
-            },
-            None => {
-                houses.insert(current_position.clone(), 1);
-            }
-        }
-    }
-
-    current_position = Position { x: 0, y: 0 };
+        current_position += *direction;
 
-    for direction in b {
-        let direction = Direction::from_str(&direction.to_string()).unwrap();
-        current_position += direction.value();
-
-        match houses.get(&current_position) {
-            Some(entry) => {
-                houses.insert(current_position.clone(), entry + 1);
-            },
-            None => {
-                houses.insert(current_position.clone(), 1);
-            }
-        }
+        houses.insert(current_position);
     }
 
-    houses.len()
-}
+    current_position = Position::ORIGIN;
 
-#[derive(Clone)]
-#[derive(Eq, Hash, PartialEq)]
-struct Position {
-    x: isize,
-    y: isize,
-}
+    for direction in b {
+        current_position += *direction;
 
-impl ops::AddAssign<Position> for Position {
-    fn add_assign(&mut self, rhs: Position) {
-        self.x += rhs.x;
-        self.y += rhs.y;
+        houses.insert(current_position);
     }
-}
 
-enum Direction {
-    North,
-    East,
-    South,
-    West,
+    houses.len()
 }
 
-impl Direction {
-    fn value(self) -> Position {
-        match self {
-            Direction::North => Position { x: 0, y: -1 },
-            Direction::East => Position { x: 1, y: 0 },
-            Direction::South => Position { x: 0, y: 1 },
-            Direction::West => Position { x: -1, y: 0 },
-        }
-    }
-}
+type Position = Point<isize>;
 
-impl FromStr for Direction {
-    type Err = Infallible;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "^" => Ok(Direction::North),
-            ">" => Ok(Direction::East),
-            "v" => Ok(Direction::South),
-            "<" => Ok(Direction::West),
-            _ => panic!("bad direction: {}", s),
-        }
-    }
+fn parse_input(input: &str) -> Result<Vec<Direction4>> {
+    input
+        .chars()
+        .map(|c| Direction4::from_arrow(c).ok_or_else(|| anyhow!("bad direction: {:?}", c)))
+        .collect()
 }
 
 #[cfg(test)]
@@ -1,27 +1,36 @@
-use std::fs;
+use crate::util::progress::Progress;
+use crate::util::runner::Runner;
+use crate::util::shape::Shape;
 use md5;
-
-pub fn solver() {
-    println!("Day 4:");
-
-    let input = fs::read_to_string("./src/years/y2015/day04.txt")
-        .expect("Should have been able to read the file");
-
-    println!("Part 1: {}", solve_part_1(input.as_str()));
-    println!("Part 2: {}", solve_part_2(input.as_str()));
+use std::time::Duration;
+
+pub fn runner() -> Runner {
+    Runner::new(2015, 4)
+        .shape(Shape::Line("[a-z]+"))
+        .part1("default", |input, progress| solve_part_1(input, progress).into())
+        .part2("default", |input, progress| solve_part_2(input, progress).into())
+        .budget(1, Duration::from_millis(250))
+        .budget(2, Duration::from_secs(1))
 }
 
-fn solve_part_1(input: &str) -> usize {
-    solve(input, "00000")
+fn solve_part_1(input: &str, progress: &Progress) -> usize {
+    solve(input, "00000", progress)
 }
 
-fn solve_part_2(input: &str) -> usize {
-    solve(input, "000000")
+fn solve_part_2(input: &str, progress: &Progress) -> usize {
+    solve(input, "000000", progress)
 }
 
-fn solve(input: &str, prefix: &str) -> usize {
+fn solve(input: &str, prefix: &str, progress: &Progress) -> usize {
     let mut current = 1;
     loop {
+        if current % 4096 == 0 {
+            progress.set(current as u64);
+            if progress.is_cancelled() {
+                return 0;
+            }
+        }
+
         let hash = md5::compute(format!("{input}{current}"));
         if format!("{:x}", hash).starts_with(prefix) {
             return current;
@@ -33,17 +42,18 @@ fn solve(input: &str, prefix: &str) -> usize {
 #[cfg(test)]
 mod tests {
     use std::fs;
+    use crate::util::progress::Progress;
     use crate::years::y2015::day04::{solve_part_1, solve_part_2};
 
     #[test]
     fn test_part_1() {
         let input = fs::read_to_string("./src/years/y2015/day04.txt").unwrap();
-        assert_eq!(solve_part_1(&input), 254575);
+        assert_eq!(solve_part_1(&input, &Progress::new()), 254575);
     }
 
     #[test]
     fn test_part_2() {
         let input = fs::read_to_string("./src/years/y2015/day04.txt").unwrap();
-        assert_eq!(solve_part_2(&input), 1038736);
+        assert_eq!(solve_part_2(&input, &Progress::new()), 1038736);
     }
 }
\ No newline at end of file
@@ -1,16 +1,14 @@
+use crate::util::runner::Runner;
+use crate::util::shape::Shape;
 use fancy_regex::Regex;
-use std::fs;
 
 type Validator<'a> = fn(&'a str) -> bool;
 
-pub fn solver() {
-    println!("Day 5:");
-
-    let input = fs::read_to_string("./src/years/y2015/day05.txt")
-        .expect("Should have been able to read the file");
-
-    println!("Part 1: {}", solve_part_1(input.as_str()));
-    println!("Part 2: {}", solve_part_2(input.as_str()));
+pub fn runner() -> Runner {
+    Runner::new(2015, 5)
+        .shape(Shape::Lines("[a-z]+"))
+        .part1("default", |input, _| solve_part_1(input).into())
+        .part2("default", |input, _| solve_part_2(input).into())
 }
 
 fn solve_part_1(input: &str) -> usize {
@@ -1,55 +1,158 @@
+use crate::util::grid::Grid;
+use crate::util::intervals::IntervalSet;
+use crate::util::parse::{self, Scanner};
+use crate::util::rng::Rng;
+use crate::util::runner::Runner;
+use crate::util::shape::Shape;
+use anyhow::{bail, Result};
+
 type Point = (usize, usize);
 type Operation = fn(usize) -> usize;
+type Command = (Operation, Point, Point);
+
+pub fn runner() -> Runner {
+    Runner::new(2015, 6)
+        .shape(Shape::Lines(
+            r"(turn on|turn off|toggle) \d+,\d+ through \d+,\d+",
+        ))
+        .parser(|input| parse_input(input, to_operation_part_1).map(drop))
+        .part1("naive", |input, _| solve_part_1(input).into())
+        .part1("compressed", |input, _| {
+            solve_part_1_compressed(input).into()
+        })
+        .part1("intervals", |input, _| solve_part_1_intervals(input).into())
+        .part2("naive", |input, _| solve_part_2(input).into())
+        .part2("compressed", |input, _| {
+            solve_part_2_compressed(input).into()
+        })
+        .generator(generate)
+}
 
 fn solve_part_1(input: &str) -> usize {
-    let commands = parse_input(input, to_operation_part_1);
+    let commands = parse_input(input, to_operation_part_1).unwrap();
     let mut grid = make_grid();
 
-    for command in &commands {
-        for y in command.1 .1..=command.2 .1 {
-            for x in command.1 .0..=command.2 .0 {
-                grid[y][x] = command.0(grid[y][x])
+    for (operation, from, to) in &commands {
+        for y in from.1..=to.1 {
+            for cell in &mut grid.row_mut(y)[from.0..=to.0] {
+                *cell = operation(*cell)
             }
         }
     }
 
-    grid.iter()
-        .fold(0, |sum, row| sum + row.iter().filter(|x| **x == 1).count())
+    grid.cells().filter(|x| **x == 1).count()
 }
 
 fn solve_part_2(input: &str) -> usize {
-    let commands = parse_input(input, to_operation_part_2);
+    let commands = parse_input(input, to_operation_part_2).unwrap();
     let mut grid = make_grid();
 
-    for command in &commands {
-        for y in command.1 .1..=command.2 .1 {
-            for x in command.1 .0..=command.2 .0 {
-                grid[y][x] = command.0(grid[y][x])
+    for (operation, from, to) in &commands {
+        for y in from.1..=to.1 {
+            for cell in &mut grid.row_mut(y)[from.0..=to.0] {
+                *cell = operation(*cell)
+            }
+        }
+    }
+
+    grid.cells().sum()
+}
+
+/// Keeps the lit lights of every row as ranges, so that each instruction
+/// touches a handful of ranges per row instead of every light.
+fn solve_part_1_intervals(input: &str) -> usize {
+    let mut rows = vec![IntervalSet::new(); 1000];
+
+    for (state, from, to) in parse_instructions(input).unwrap() {
+        for row in &mut rows[from.1..=to.1] {
+            match state {
+                "on" => row.insert(from.0..to.0 + 1),
+                "off" => row.remove(from.0..to.0 + 1),
+                _ => row.toggle(from.0..to.0 + 1),
+            }
+        }
+    }
+
+    rows.iter().map(IntervalSet::len).sum()
+}
+
+fn solve_part_1_compressed(input: &str) -> usize {
+    solve_compressed(&parse_input(input, to_operation_part_1).unwrap())
+}
+
+fn solve_part_2_compressed(input: &str) -> usize {
+    solve_compressed(&parse_input(input, to_operation_part_2).unwrap())
+}
+
+/// Cuts the grid along every edge of every instruction, so that each instruction
+/// covers whole compressed cells and never has to touch a light individually.
+fn solve_compressed(commands: &[Command]) -> usize {
+    let xs = boundaries(commands.iter().flat_map(|(_, from, to)| [from.0, to.0 + 1]));
+    let ys = boundaries(commands.iter().flat_map(|(_, from, to)| [from.1, to.1 + 1]));
+    let mut grid = Grid::new(xs.len() - 1, ys.len() - 1, 0);
+
+    for (operation, from, to) in commands {
+        let (x_start, x_end) = (position(&xs, from.0), position(&xs, to.0 + 1));
+        let (y_start, y_end) = (position(&ys, from.1), position(&ys, to.1 + 1));
+
+        for y in y_start..y_end {
+            for cell in &mut grid.row_mut(y)[x_start..x_end] {
+                *cell = operation(*cell)
             }
         }
     }
 
-    grid.iter().fold(0, |sum, row| {
-        sum + row.iter().fold(0, |sum, cell| sum + cell)
+    grid.iter().fold(0, |sum, ((x, y), cell)| {
+        sum + cell * (xs[x + 1] - xs[x]) * (ys[y + 1] - ys[y])
     })
 }
 
-fn make_grid() -> Vec<Vec<usize>> {
-    vec![vec![0; 1000]; 1000]
+fn boundaries(values: impl Iterator<Item = usize>) -> Vec<usize> {
+    let mut boundaries: Vec<usize> = values.collect();
+    boundaries.sort();
+    boundaries.dedup();
+    boundaries
 }
 
-fn parse_input(
-    input: &str,
-    operation_mapper: fn(&str) -> Operation,
-) -> Vec<(Operation, Point, Point)> {
-    input
-        .lines()
-        .map(|line| match line.split(' ').collect::<Vec<_>>()[..] {
-            [_, state, from, _, to] => (operation_mapper(state), to_point(from), to_point(to)),
-            [state, from, _, to] => (operation_mapper(state), to_point(from), to_point(to)),
-            _ => unreachable!(),
-        })
-        .collect()
+fn position(boundaries: &[usize], value: usize) -> usize {
+    boundaries.binary_search(&value).unwrap()
+}
+
+fn make_grid() -> Grid<usize> {
+    Grid::new(1000, 1000, 0)
+}
+
+fn parse_input(input: &str, operation_mapper: fn(&str) -> Operation) -> Result<Vec<Command>> {
+    Ok(parse_instructions(input)?
+        .into_iter()
+        .map(|(state, from, to)| (operation_mapper(state), from, to))
+        .collect())
+}
+
+/// Every instruction as its state (`on`, `off` or `toggle`) and its corners.
+fn parse_instructions(input: &str) -> Result<Vec<(&'static str, Point, Point)>> {
+    parse::parse_lines_with(input, |line| {
+        let mut scanner = Scanner::new(line);
+        let state = if scanner.try_literal("turn ") {
+            scanner.one_of(&["on", "off"])?
+        } else {
+            scanner.one_of(&["toggle"])?
+        };
+        scanner.literal(" ")?;
+        let from = to_point(&mut scanner)?;
+        scanner.literal(" through ")?;
+        let to = to_point(&mut scanner)?;
+        scanner.finish()?;
+
+        if from.0 > to.0 || from.1 > to.1 {
+            bail!(
+                "{:?} does not go from the top left to the bottom right corner",
+                line
+            );
+        }
+
+        Ok((state, from, to))
+    })
 }
 
 fn to_operation_part_1(string: &str) -> Operation {
@@ -70,14 +173,40 @@ fn to_operation_part_2(string: &str) -> Operation {
     }
 }
 
-fn to_point(string: &str) -> Point {
-    let (x, y) = string.split_once(',').unwrap();
-    (x.parse::<usize>().unwrap(), y.parse::<usize>().unwrap())
+fn to_point(scanner: &mut Scanner) -> Result<Point> {
+    let point = scanner.pair::<usize>(",")?;
+    if point.0 >= 1000 || point.1 >= 1000 {
+        bail!("{},{} is outside of the grid", point.0, point.1);
+    }
+
+    Ok(point)
+}
+
+/// Generates `size` instructions over random rectangles.
+fn generate(size: usize, rng: &mut Rng) -> String {
+    (0..size)
+        .map(|_| {
+            let (x1, x2) = (rng.range(0..1000), rng.range(0..1000));
+            let (y1, y2) = (rng.range(0..1000), rng.range(0..1000));
+            format!(
+                "{} {},{} through {},{}",
+                rng.choose(&["turn on", "turn off", "toggle"]),
+                x1.min(x2),
+                y1.min(y2),
+                x1.max(x2),
+                y1.max(y2)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::years::y2015::day06::{solve_part_1, solve_part_2};
+    use crate::years::y2015::day06::{
+        solve_part_1, solve_part_1_compressed, solve_part_1_intervals, solve_part_2,
+        solve_part_2_compressed,
+    };
     use std::fs;
 
     #[test]
@@ -91,4 +220,22 @@ mod tests {
         let input = fs::read_to_string("./src/years/y2015/day06.txt").unwrap();
         assert_eq!(solve_part_2(&input), 15343601);
     }
+
+    #[test]
+    fn test_input_part_1_compressed() {
+        let input = fs::read_to_string("./src/years/y2015/day06.txt").unwrap();
+        assert_eq!(solve_part_1_compressed(&input), 400410);
+    }
+
+    #[test]
+    fn test_input_part_1_intervals() {
+        let input = fs::read_to_string("./src/years/y2015/day06.txt").unwrap();
+        assert_eq!(solve_part_1_intervals(&input), 400410);
+    }
+
+    #[test]
+    fn test_input_part_2_compressed() {
+        let input = fs::read_to_string("./src/years/y2015/day06.txt").unwrap();
+        assert_eq!(solve_part_2_compressed(&input), 15343601);
+    }
 }
@@ -1,17 +1,22 @@
+use crate::util::runner::Runner;
+use std::time::Duration;
+
 pub mod day01;
 pub mod day02;
 pub mod day03;
 pub mod day04;
 pub mod day05;
-mod day06;
+pub mod day06;
+
+pub const TIME_LIMIT: Duration = Duration::from_secs(2);
 
-pub fn get_solver(day: u8) -> fn() {
-    match day {
-        1 => day01::solver,
-        2 => day02::solver,
-        3 => day03::solver,
-        4 => day04::solver,
-        5 => day05::solver,
-        _ => panic!("Unknown day: {}", day),
-    }
+pub fn runners() -> Vec<Runner> {
+    vec![
+        day01::runner(),
+        day02::runner(),
+        day03::runner(),
+        day04::runner(),
+        day05::runner(),
+        day06::runner(),
+    ]
 }
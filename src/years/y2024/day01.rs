@@ -1,18 +1,21 @@
-use std::fs;
+use crate::util::counter::Counter;
+use crate::util::rng::Rng;
+use crate::util::runner::Runner;
+use crate::util::shape::Shape;
+use anyhow::{anyhow, Result};
 use std::iter::zip;
 
-pub fn solver() {
-    println!("Day 1:");
-
-    let input = fs::read_to_string("./src/years/y2024/day01.txt")
-        .expect("Should have been able to read the file");
-
-    println!("Part 1: {}", solve_part_1(input.as_str()));
-    println!("Part 2: {}", solve_part_2(input.as_str()));
+pub fn runner() -> Runner {
+    Runner::new(2024, 1)
+        .shape(Shape::Lines(r"\d+ +\d+"))
+        .parser(|input| get_lists(input).map(drop))
+        .part1("default", |input, _| solve_part_1(input).into())
+        .part2("default", |input, _| solve_part_2(input).into())
+        .generator(generate)
 }
 
 fn solve_part_1(input: &str) -> usize {
-    let (mut list_a, mut list_b): (Vec<usize>, Vec<usize>) = get_lists(input);
+    let (mut list_a, mut list_b): (Vec<usize>, Vec<usize>) = get_lists(input).unwrap();
 
     list_a.sort();
     list_b.sort();
@@ -23,21 +26,31 @@ fn solve_part_1(input: &str) -> usize {
 }
 
 fn solve_part_2(input: &str) -> usize {
-    let (list_a, list_b): (Vec<usize>, Vec<usize>) = get_lists(input);
+    let (list_a, list_b): (Vec<usize>, Vec<usize>) = get_lists(input).unwrap();
+    let occurrences: Counter<usize> = list_b.into_iter().collect();
 
     list_a.iter()
-        .map(|&a| a * list_b.iter().filter(|&&b| b == a).count())
+        .map(|&a| a * occurrences[&a])
         .sum::<usize>()
 }
 
-fn get_lists(input: &str) -> (Vec<usize>, Vec<usize>) {
+fn get_lists(input: &str) -> Result<(Vec<usize>, Vec<usize>)> {
     input.trim()
         .split("\n")
         .map(|x| {
-            let (a, b) = x.split_once(char::is_whitespace).unwrap();
-            (a.parse::<usize>().unwrap(), b.trim().parse::<usize>().unwrap())
+            let (a, b) = x.split_once(char::is_whitespace)
+                .ok_or_else(|| anyhow!("expected two location ids but found {:?}", x))?;
+            Ok((a.parse::<usize>()?, b.trim().parse::<usize>()?))
         })
-        .unzip()
+        .collect()
+}
+
+/// Generates `size` pairs of location ids.
+fn generate(size: usize, rng: &mut Rng) -> String {
+    (0..size)
+        .map(|_| format!("{}   {}", rng.range(10000..100000), rng.range(10000..100000)))
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 #[cfg(test)]
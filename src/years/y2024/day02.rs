@@ -1,24 +1,25 @@
-use std::fs;
-
-pub fn solver() {
-    println!("Day 2:");
-
-    let input =
-        fs::read_to_string("./src/years/y2024/day02.txt").expect("Should have been able to read the file");
-
-    println!("Part 1: {}", solve_part_1(input.as_str()));
-    println!("Part 2: {}", solve_part_2(input.as_str()));
+use crate::util::parse;
+use crate::util::runner::Runner;
+use crate::util::shape::Shape;
+use anyhow::Result;
+
+pub fn runner() -> Runner {
+    Runner::new(2024, 2)
+        .shape(Shape::Lines(r"\d+( \d+)*"))
+        .parser(|input| get_reports(input).map(drop))
+        .part1("default", |input, _| solve_part_1(input).into())
+        .part2("default", |input, _| solve_part_2(input).into())
 }
 
-fn is_ascending(report: &Vec<usize>) -> bool {
+fn is_ascending(report: &[usize]) -> bool {
     report.windows(2).all(|w| w[0] < w[1])
 }
 
-fn is_descending(report: &Vec<usize>) -> bool {
+fn is_descending(report: &[usize]) -> bool {
     report.windows(2).all(|w| w[0] > w[1])
 }
 
-fn is_tight(report: &Vec<usize>, max_gap: usize) -> bool {
+fn is_tight(report: &[usize], max_gap: usize) -> bool {
     report.windows(2).all(|w| w[0].abs_diff(w[1]) <= max_gap)
 }
 
@@ -28,6 +29,7 @@ fn is_valid(report: &&Vec<usize>) -> bool {
 
 fn solve_part_1(input: &str) -> usize {
     get_reports(input)
+        .unwrap()
         .iter()
         .filter(is_valid)
         .count()
@@ -35,6 +37,7 @@ fn solve_part_1(input: &str) -> usize {
 
 fn solve_part_2(input: &str) -> usize {
     get_reports(input)
+        .unwrap()
         .iter()
         .filter(|&report| {
             for i in 0..report.len() {
@@ -50,17 +53,12 @@ fn solve_part_2(input: &str) -> usize {
         .count()
 }
 
-fn get_reports(input: &str) -> Vec<Vec<usize>> {
-    input.trim().split("\n")
-        .map(|level| {
-            level.split_whitespace()
-                .map(|num| num.parse::<usize>().unwrap())
-                .collect()
-        })
-        .collect()
+fn get_reports(input: &str) -> Result<Vec<Vec<usize>>> {
+    parse::parse_lines_with(input.trim(), |report| parse::separated(report, " "))
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison, clippy::useless_vec)]
 mod tests {
     use std::fs;
     use crate::years::y2024::day02::{is_ascending, is_descending, is_tight, solve_part_1, solve_part_2};
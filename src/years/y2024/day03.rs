@@ -1,14 +1,11 @@
-use std::fs;
+use crate::util::parse;
+use crate::util::runner::Runner;
 use regex::Regex;
 
-pub fn solver() {
-    println!("Day 3:");
-
-    let input =
-        fs::read_to_string("./src/years/y2024/day03.txt").expect("Should have been able to read the file");
-
-    println!("Part 1: {}", solve_part_1(input.as_str()));
-    println!("Part 2: {}", solve_part_2(input.as_str()));
+pub fn runner() -> Runner {
+    Runner::new(2024, 3)
+        .part1("default", |input, _| solve_part_1(input).into())
+        .part2("default", |input, _| solve_part_2(input).into())
 }
 
 
@@ -21,7 +18,7 @@ fn solve_part_1(input: &str) -> usize {
 
 fn solve_part_2(input: &str) -> usize {
     let ops_regex = Regex::new(r#"mul(\(\d+,\d+\))|(don't\(\)|(do\(\)))"#).unwrap();
-    let ops = ops_regex.find_iter(&input);
+    let ops = ops_regex.find_iter(input);
 
     let mut enabled = true;
     let mut sum = 0;
@@ -44,12 +41,8 @@ fn solve_part_2(input: &str) -> usize {
 }
 
 fn mult(input: &str) -> usize {
-    let digits_regex = Regex::new(r#"\d+"#).unwrap();
-    let digits: Vec<usize> = digits_regex
-        .find_iter(input)
-        .map(|x| x.as_str().parse::<usize>().unwrap())
-        .collect();
-    digits[0] * digits[1]
+    let [a, b] = parse::exact_integers::<usize, 2>(input).unwrap();
+    a * b
 }
 
 #[cfg(test)]
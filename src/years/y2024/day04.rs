@@ -1,106 +1,43 @@
-use anyhow::{anyhow, Result};
-use std::{fs, ops};
-
-pub fn solver() {
-    println!("Day 4:");
-
-    let input = fs::read_to_string("./src/years/y2024/day04.txt")
-        .expect("Should have been able to read the file");
-
-    println!("Part 1: {}", solve_part_1(input.as_str()));
-    println!("Part 2: {}", solve_part_2(input.as_str()));
-}
-
-#[derive(Copy, Clone, Debug)]
-struct Direction {
-    x: isize,
-    y: isize,
-}
-
-struct Position {
-    x: usize,
-    y: usize,
-}
-
-impl ops::Add<Direction> for Position {
-    type Output = Result<Position>;
-
-    fn add(self, rhs: Direction) -> Result<Position> {
-        let (x, y) = (self.x, self.y);
-        let new_x: usize = (x as isize + rhs.x).try_into()?;
-        let new_y: usize = (y as isize + rhs.y).try_into()?;
-
-        Ok(Position { x: new_x, y: new_y })
-    }
-}
-
-struct Matrix {
-    matrix: Vec<Vec<char>>,
+use crate::util::geometry::{Direction8, Point};
+use crate::util::grid::Grid;
+use crate::util::runner::Runner;
+use crate::util::shape::Shape;
+use anyhow::Result;
+
+pub fn runner() -> Runner {
+    Runner::new(2024, 4)
+        .shape(Shape::Grid("[XMAS]"))
+        .parser(|input| parse_input(input).map(drop))
+        .part1("default", |input, _| solve_part_1(input).into())
+        .part2("default", |input, _| solve_part_2(input).into())
 }
 
-impl Matrix {
-    fn get_cell(&self, x: usize, y: usize) -> Result<char> {
-        let row = self.get_row(y)?;
-        match row.get(x) {
-            Some(cell) => Ok(*cell),
-            None => Err(anyhow!("X:{x} not found in matrix")),
-        }
-    }
-
-    fn get_row(&self, y: usize) -> Result<Vec<char>> {
-        match self.matrix.get(y) {
-            Some(row) => Ok(row.to_vec()),
-            None => Err(anyhow!("Row with y:{y} not found in matrix")),
-        }
-    }
-}
+type Position = Point<i64>;
 
 fn solve_part_1(input: &str) -> usize {
-    const DELTAS: [Direction; 8] = [
-        Direction { x: 1, y: 0 },
-        Direction { x: 1, y: 1 },
-        Direction { x: 0, y: 1 },
-        Direction { x: -1, y: 1 },
-        Direction { x: -1, y: 0 },
-        Direction { x: -1, y: -1 },
-        Direction { x: 0, y: -1 },
-        Direction { x: 1, y: -1 },
-    ];
-
     let search_word = ['X', 'M', 'A', 'S'];
-    let input: Vec<Vec<char>> = input.split("\n").map(|x| x.chars().collect()).collect();
-    let matrix = Matrix { matrix: input };
+    let grid = parse_input(input).unwrap();
 
     let mut matches = 0;
 
-    for (y, row) in matrix.matrix.iter().enumerate() {
-        for (x, cell) in row.iter().enumerate() {
-            if *cell == search_word[0] {
-                'directions: for delta in DELTAS.iter() {
-                    for (i, char) in search_word.iter().enumerate() {
-                        let position = Position { x, y };
-                        let delta = Direction {
-                            x: delta.x * i as isize,
-                            y: delta.y * i as isize,
-                        };
-
-                        let new_position = match position + delta {
-                            Ok(position) => position,
-                            Err(_) => continue 'directions,
-                        };
-
-                        let cell = match matrix.get_cell(new_position.x, new_position.y) {
-                            Ok(x) => x,
-                            Err(_) => continue 'directions,
-                        };
-
-                        if *char != cell {
-                            continue 'directions;
-                        }
-
-                        if i == search_word.len() - 1 {
-                            matches += 1;
-                        }
+    for (position, cell) in grid.iter() {
+        if *cell == search_word[0] {
+            'directions: for direction in Direction8::ALL {
+                for (i, char) in search_word.iter().enumerate() {
+                    let position = Position::from_position(position).unwrap();
+                    let new_position = position + direction.offset() * i as i64;
+
+                    let cell = match grid.get_point(new_position) {
+                        Some(cell) => cell,
+                        None => continue 'directions,
+                    };
+
+                    if char != cell {
+                        continue 'directions;
+                    }
+
+                    if i == search_word.len() - 1 {
+                        matches += 1;
                     }
                 }
             }
@@ -111,28 +48,24 @@ fn solve_part_1(input: &str) -> usize {
 }
 
 fn solve_part_2(input: &str) -> usize {
-    const MASKS: [[Direction; 2]; 2] = [
-        [Direction { x: -1, y: -1 }, Direction { x: 1, y: 1 }],
-        [Direction { x: -1, y: 1 }, Direction { x: 1, y: -1 }],
+    const MASKS: [[Direction8; 2]; 2] = [
+        [Direction8::NorthWest, Direction8::SouthEast],
+        [Direction8::SouthWest, Direction8::NorthEast],
     ];
 
-    let input: Vec<Vec<char>> = input.split("\n").map(|x| x.chars().collect()).collect();
-    let matrix = Matrix { matrix: input };
+    let grid = parse_input(input).unwrap();
 
     let mut matches = 0;
 
-    for y in 1..matrix.matrix.len() - 1 {
-        let row = matrix.get_row(y).unwrap();
-        for x in 1..row.len() - 1 {
-            let cell = matrix.get_cell(x, y).unwrap();
-            if cell == 'A' {
+    for y in 1..grid.height() - 1 {
+        for x in 1..grid.width() - 1 {
+            if grid[(x, y)] == 'A' {
                 let is_x_mas = MASKS.iter().all(|mask| {
                     let cells: Vec<char> = mask
                         .iter()
                         .map(|&direction| {
-                            let position = Position { x, y };
-                            let delta = (position + direction).unwrap();
-                            matrix.get_cell(delta.x, delta.y).unwrap()
+                            let position = Position::new(x as i64, y as i64);
+                            *grid.get_point(position + direction).unwrap()
                         })
                         .collect();
 
@@ -149,6 +82,10 @@ fn solve_part_2(input: &str) -> usize {
     matches
 }
 
+fn parse_input(input: &str) -> Result<Grid<char>> {
+    Grid::parse(input)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::years::y2024::day04::{solve_part_1, solve_part_2};
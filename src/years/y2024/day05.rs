@@ -1,16 +1,19 @@
+use crate::util::parse::{self, Scanner};
+use crate::util::runner::Runner;
+use crate::util::shape::Shape;
+use anyhow::{bail, Result};
 use std::collections::{HashMap, HashSet};
-use std::convert::Infallible;
-use std::fs;
 use std::str::FromStr;
 
-pub fn solver() {
-    println!("Day 5:");
-
-    let input = fs::read_to_string("./src/years/y2024/day05.txt")
-        .expect("Should have been able to read the file");
-
-    println!("Part 1: {}", solve_part_1(input.as_str()));
-    println!("Part 2: {}", solve_part_2(input.as_str()));
+pub fn runner() -> Runner {
+    Runner::new(2024, 5)
+        .shape(Shape::Sections(vec![
+            Shape::Lines(r"\d+\|\d+"),
+            Shape::Lines(r"\d+(,\d+)*"),
+        ]))
+        .parser(|input| Model::from_str(input).map(drop))
+        .part1("default", |input, _| solve_part_1(input).into())
+        .part2("default", |input, _| solve_part_2(input).into())
 }
 
 fn solve_part_1(input: &str) -> usize {
@@ -47,7 +50,7 @@ struct Model {
     updates: Vec<Vec<u8>>,
 }
 
-fn is_valid(update: &Vec<u8>, model: &Model) -> bool {
+fn is_valid(update: &[u8], model: &Model) -> bool {
     for (i, val) in update.iter().enumerate() {
         let rules = match model.rules.get(val) {
             Some(rules) => rules,
@@ -61,14 +64,14 @@ fn is_valid(update: &Vec<u8>, model: &Model) -> bool {
     true
 }
 
-fn make_valid(invalid_update: &Vec<u8>, rules: HashMap<u8, HashSet<u8>>) -> Vec<u8> {
+fn make_valid(invalid_update: &[u8], rules: HashMap<u8, HashSet<u8>>) -> Vec<u8> {
     let mut result = Vec::new();
 
     for val in invalid_update {
         let rules = match rules.get(val) {
             Some(rules) => rules,
             None => {
-                result.insert(0, val.clone());
+                result.insert(0, *val);
                 continue
             },
         };
@@ -82,7 +85,7 @@ fn make_valid(invalid_update: &Vec<u8>, rules: HashMap<u8, HashSet<u8>>) -> Vec<
             })
             .max()
             .unwrap();
-        result.insert(max_index as usize, val.clone());
+        result.insert(max_index, *val);
     }
 
     result
@@ -107,37 +110,28 @@ impl Model {
 }
 
 impl FromStr for Model {
-    type Err = Infallible;
+    type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (rules, updates) = s.split_once("\n\n").unwrap();
+        let (rules, updates) = match parse::blocks(s).collect::<Vec<_>>()[..] {
+            [rules, updates] => (rules, updates),
+            _ => bail!("expected rules and updates separated by a blank line"),
+        };
 
-        let rules = rules
-            .split("\n")
-            .map(|rule| {
-                let (a, b) = rule.split_once("|").unwrap();
-                (a.parse::<u8>().unwrap(), b.parse::<u8>().unwrap())
-            })
-            .fold(HashMap::new(), |mut map: HashMap<u8, HashSet<u8>>, rule| {
-                if !map.contains_key(&rule.1) {
-                    map.insert(rule.1, HashSet::new());
-                }
+        let rules = parse::parse_lines_with(rules, |rule| {
+            let mut scanner = Scanner::new(rule);
+            let rule = scanner.pair::<u8>("|")?;
+            scanner.finish()?;
+            Ok(rule)
+        })?
+        .into_iter()
+        .fold(HashMap::new(), |mut map: HashMap<u8, HashSet<u8>>, rule| {
+            map.entry(rule.1).or_default().insert(rule.0);
+            map
+        });
 
-                map.entry(rule.1).and_modify(|set| {
-                    set.insert(rule.0);
-                });
-                map
-            });
-
-        let updates = updates
-            .split("\n")
-            .map(|update| {
-                update
-                    .split(",")
-                    .map(|val| val.parse::<u8>().unwrap())
-                    .collect()
-            })
-            .collect();
+        let updates =
+            parse::parse_lines_with(updates, |update| parse::separated::<u8>(update, ","))?;
 
         Ok(Model { rules, updates })
     }
@@ -145,8 +139,10 @@ impl FromStr for Model {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::{HashMap, HashSet};
     use std::fs;
-    use crate::years::y2024::day05::{solve_part_1, solve_part_2};
+    use crate::util::check::agree;
+    use crate::years::y2024::day05::{is_valid, make_valid, solve_part_1, solve_part_2, Model};
 
     #[test]
     fn test_input_part_1() {
@@ -231,4 +227,65 @@ mod tests {
         let input = fs::read_to_string("./src/years/y2024/day05.txt").unwrap();
         assert_eq!(solve_part_2(&input), 4260)
     }
+
+    /// Orders pages by a fixed scrambled rank, so that every pair of pages has a rule.
+    fn rank(page: u8) -> u32 {
+        (page as u32 * 57 + 13) % 101
+    }
+
+    fn rules_for(pages: &[u8]) -> HashMap<u8, HashSet<u8>> {
+        let mut rules: HashMap<u8, HashSet<u8>> = HashMap::new();
+        for &a in pages {
+            for &b in pages {
+                if rank(a) < rank(b) {
+                    rules.entry(b).or_default().insert(a);
+                }
+            }
+        }
+        rules
+    }
+
+    fn permutations(pages: &[u8]) -> Vec<Vec<u8>> {
+        if pages.len() <= 1 {
+            return vec![pages.to_vec()];
+        }
+        (0..pages.len())
+            .flat_map(|i| {
+                let mut rest = pages.to_vec();
+                let first = rest.remove(i);
+                permutations(&rest).into_iter().map(move |mut permutation| {
+                    permutation.insert(0, first);
+                    permutation
+                })
+            })
+            .collect()
+    }
+
+    fn distinct(pages: &[u8]) -> Vec<u8> {
+        let mut seen = HashSet::new();
+        pages
+            .iter()
+            .map(|page| page % 100)
+            .filter(|page| seen.insert(*page))
+            .collect()
+    }
+
+    #[test]
+    fn test_make_valid_against_brute_force() {
+        agree(
+            300,
+            |rng| (0..rng.range(1..7)).map(|_| rng.range(0..100) as u8).collect::<Vec<u8>>(),
+            |pages| {
+                let update = distinct(pages);
+                let model = Model { rules: rules_for(&update), updates: Vec::new() };
+                permutations(&update)
+                    .into_iter()
+                    .find(|permutation| is_valid(permutation, &model))
+            },
+            |pages| {
+                let update = distinct(pages);
+                Some(make_valid(&update, rules_for(&update)))
+            },
+        );
+    }
 }
@@ -1,79 +1,116 @@
-use std::collections::{HashMap, HashSet};
-use std::{fs};
-
-type Point = (i32, i32);
-type Map = HashMap<Point, char>;
-
-pub fn solver() {
-    println!("Day 6:");
-
-    let input = fs::read_to_string("./src/years/y2024/day06.txt")
-        .expect("Should have been able to read the file");
-
-    println!("Part 1: {}", solve_part_1(input.as_str()));
-    println!("Part 2: {}", solve_part_2(input.as_str()));
+use crate::event;
+use crate::util::bitset::BitGrid;
+use crate::util::geometry::{Direction4, Point};
+use crate::util::grid::{self, Grid};
+use crate::util::progress::Progress;
+use crate::util::runner::Runner;
+use crate::util::shape::Shape;
+use crate::util::trace;
+use crate::util::trace::Level;
+use anyhow::{bail, Result};
+use std::time::Duration;
+
+type Position = Point<i32>;
+type Map = Grid<char>;
+
+pub fn runner() -> Runner {
+    Runner::new(2024, 6)
+        .shape(Shape::Grid("[.#^]"))
+        .parser(|input| parse_input(input).map(drop))
+        .part1("default", |input, _| solve_part_1(input).into())
+        .part2("default", |input, progress| {
+            solve_part_2(input, progress).into()
+        })
+        .budget(2, Duration::from_millis(750))
 }
 
 fn solve_part_1(input: &str) -> usize {
-    let map = parse_input(input);
+    let map = parse_input(input).unwrap();
     let start_position = get_start_position(&map);
     get_visited_positions(&map, start_position).len()
 }
 
-fn solve_part_2(input: &str) -> usize {
-    let map = parse_input(input);
+fn solve_part_2(input: &str, progress: &Progress) -> usize {
+    let map = parse_input(input).unwrap();
     let start_position = get_start_position(&map);
     let visited_positions = get_visited_positions(&map, start_position);
+    event!(
+        Level::Info,
+        "Testing {} candidate obstacles",
+        visited_positions.len()
+    );
+
+    progress.set_total(visited_positions.len() as u64);
+
+    // Reused for every candidate, so that checking one only has to clear it
+    let mut seen = BitGrid::with_states(map.width(), map.height(), 4);
     visited_positions
-        .iter()
-        .filter(|pos| {
-            let map = with_obstacle(map.clone(), **pos);
-            is_loop(&map, start_position)
+        .positions()
+        .take_while(|_| !progress.is_cancelled())
+        .filter(|&pos| {
+            let map = with_obstacle(map.clone(), pos);
+            let looped = is_loop(&map, start_position, &mut seen);
+            if looped {
+                event!(Level::Debug, "Obstacle at {:?} creates a loop", pos);
+            }
+            progress.advance(1);
+            looped
         })
         .count()
 }
 
-fn with_obstacle(mut map: Map, at: Point) -> Map {
-    map.insert(at, '#');
+fn with_obstacle(mut map: Map, at: grid::Position) -> Map {
+    map[at] = '#';
     map
 }
 
-fn is_loop(map: &Map, start_position: Point) -> bool {
-    let mut current_position = start_position.clone();
-    let mut current_direction = Direction::North;
-    let mut visited = HashSet::new();
-
-    loop {
-        let next_position = go_forward(&current_direction, current_position);
-
-        match map.get(&next_position) {
-            None => return false,
-            Some(char) => {
-                if char == &'#' {
-                    if visited.contains(&(current_position, current_direction)) {
-                        return true;
-                    }
-                    visited.insert((current_position, current_direction));
-                    current_direction = turn(&current_direction);
-                } else {
-                    current_position = next_position;
+/// Whether the guard walks in circles, tracking the position and direction of
+/// every turn with `seen`.
+fn is_loop(map: &Map, start_position: Position, seen: &mut BitGrid) -> bool {
+    seen.clear();
+    let mut position = start_position;
+    let mut direction = Direction4::North;
+    let mut steps = 0;
+
+    // Every loop has a turn in it, so only remembering the turns is enough to
+    // notice one
+    let looped = loop {
+        steps += 1;
+        let next_position = position + direction;
+        match map.get_point(next_position) {
+            None => break false,
+            Some('#') => {
+                if !seen.insert_state(position.position().unwrap(), direction as usize) {
+                    break true;
                 }
+                direction = direction.turn_right();
             }
+            _ => position = next_position,
         }
+    };
+
+    trace::count("steps", steps);
+    if looped {
+        trace::count("loops", 1);
+        event!(Level::Trace, "Loop detected after {} steps", steps);
     }
+    looped
 }
 
-fn get_visited_positions(map: &Map, start_position: Point) -> HashSet<Point> {
-    let mut current_position = start_position.clone();
-    let mut current_direction = Direction::North;
-    let mut visited: HashSet<Point> = HashSet::new();
+fn get_visited_positions(map: &Map, start_position: Position) -> BitGrid {
+    let mut current_position = start_position;
+    let mut current_direction = Direction4::North;
+    let mut visited = BitGrid::new(map.width(), map.height());
 
-    while map.contains_key(&current_position) {
-        visited.insert(current_position);
-        let next_position = go_forward(&current_direction, current_position);
+    while let Some(cell) = current_position
+        .position()
+        .filter(|&cell| map.get(cell).is_some())
+    {
+        visited.insert(cell);
+        let next_position = current_position + current_direction;
 
-        if map.get(&next_position) == Some(&'#') {
-            current_direction = turn(&current_direction);
+        if map.get_point(next_position) == Some(&'#') {
+            current_direction = current_direction.turn_right();
         } else {
             current_position = next_position;
         }
@@ -82,51 +119,30 @@ fn get_visited_positions(map: &Map, start_position: Point) -> HashSet<Point> {
     visited
 }
 
-fn turn(direction: &Direction) -> Direction {
-    match direction {
-        Direction::North => Direction::East,
-        Direction::East => Direction::South,
-        Direction::South => Direction::West,
-        Direction::West => Direction::North,
-    }
-}
+fn parse_input(input: &str) -> Result<Map> {
+    let map = Grid::parse_with(input, |c| match c {
+        '.' | '#' | '^' => Ok(c),
+        _ => bail!("expected one of '.', '#' or '^'"),
+    })?;
 
-fn go_forward(direction: &Direction, (x, y): Point) -> Point {
-    match direction {
-        Direction::North => (x, y - 1),
-        Direction::East => (x + 1, y),
-        Direction::South => (x, y + 1),
-        Direction::West => (x - 1, y),
+    let guards = map.cells().filter(|&&c| c == '^').count();
+    if guards != 1 {
+        bail!("expected a single guard but found {}", guards);
     }
-}
 
-fn parse_input(input: &str) -> Map {
-    let mut map: Map = HashMap::new();
-    input.lines().enumerate().for_each(|(y, line)| {
-        line.chars().enumerate().for_each(|(x, c)| {
-            map.insert((x as i32, y as i32), c);
-        })
-    });
-    map
+    Ok(map)
 }
 
-fn get_start_position(map: &Map) -> Point {
-    match map.iter().enumerate().find(|(_, (_, &char))| char == '^') {
-        Some((_, (point, _))) => point.clone(),
+fn get_start_position(map: &Map) -> Position {
+    match map.find(&'^') {
+        Some(position) => Position::from_position(position).unwrap(),
         None => panic!("Could not find start position"),
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-enum Direction {
-    North,
-    East,
-    South,
-    West,
-}
-
 #[cfg(test)]
 mod tests {
+    use crate::util::progress::Progress;
     use crate::years::y2024::day06::{solve_part_1, solve_part_2};
     use std::fs;
 
@@ -169,12 +185,12 @@ mod tests {
 ......#..."#
             .trim();
 
-        assert_eq!(solve_part_2(input), 6);
+        assert_eq!(solve_part_2(input, &Progress::new()), 6);
     }
 
     #[test]
     fn test_part_2() {
         let input = fs::read_to_string("./src/years/y2024/day06.txt").unwrap();
-        assert_eq!(solve_part_2(&input), 1309);
+        assert_eq!(solve_part_2(&input, &Progress::new()), 1309);
     }
 }
@@ -1,4 +1,11 @@
-use std::fs;
+use crate::util::math;
+use crate::util::math::{digits, split_digits};
+use crate::util::parse::{self, Scanner};
+use crate::util::rng::Rng;
+use crate::util::runner::Runner;
+use crate::util::shape::Shape;
+use anyhow::{bail, Result};
+use std::collections::HashSet;
 
 trait Operation {
     fn calc(&self, a: i64, b: i64) -> i64;
@@ -30,42 +37,31 @@ impl Operation for Divide {
 struct Concatenate;
 
 impl Operation for Concatenate {
-    fn calc(&self, a: i64, mut b: i64) -> i64 {
-        let mut multiplier = 1;
-        while b > 0 {
-            b /= 10;
-            multiplier *= 10;
-        }
-
-        a / multiplier
+    fn calc(&self, a: i64, b: i64) -> i64 {
+        split_digits(a as u64, digits(b as u64)).0 as i64
     }
     fn should_continue(&self, a: i64, b: i64) -> bool {
-        let mut multiplier = 1;
-        let mut c = b;
-        while c > 0 {
-            c /= 10;
-            multiplier *= 10;
-        }
-
-        (a % multiplier) == b
+        split_digits(a as u64, digits(b as u64)).1 == b as u64
     }
 }
 
-pub fn solver() {
-    println!("Day 7:");
-
-    let input = fs::read_to_string("./src/years/y2024/day07.txt")
-        .expect("Should have been able to read the file");
-
-    println!("Part 1: {}", solve_part_1(input.as_str()));
-    println!("Part 2: {}", solve_part_2(input.as_str()));
+pub fn runner() -> Runner {
+    Runner::new(2024, 7)
+        .shape(Shape::Lines(r"\d+:( \d+)+"))
+        .parser(|input| parse_input(input).map(drop))
+        .part1("recursive", |input, _| solve_part_1(input).into())
+        .part1("forward", |input, _| solve_part_1_forward(input).into())
+        .part2("recursive", |input, _| solve_part_2(input).into())
+        .part2("forward", |input, _| solve_part_2_forward(input).into())
+        .generator(generate)
 }
 
 fn solve_part_1(input: &str) -> i64 {
     let ops: [Box<dyn Operation>; 2] = [Box::new(Subtract), Box::new(Divide)];
     parse_input(input)
+        .unwrap()
         .iter()
-        .filter(|&(target, values)| is_solveable((target.clone(), values), &ops))
+        .filter(|&(target, values)| is_solveable((*target, values), &ops))
         .fold(0, |sum, (value, _)| sum + value)
 }
 
@@ -73,8 +69,9 @@ fn solve_part_2(input: &str) -> i64 {
     let ops: [Box<dyn Operation>; 3] =
         [Box::new(Subtract), Box::new(Divide), Box::new(Concatenate)];
     parse_input(input)
+        .unwrap()
         .iter()
-        .filter(|&(target, values)| is_solveable((target.clone(), values), &ops))
+        .filter(|&(target, values)| is_solveable((*target, values), &ops))
         .fold(0, |sum, (value, _)| sum + value)
 }
 
@@ -93,25 +90,93 @@ fn is_solveable(equation: (i64, &[i64]), ops: &[Box<dyn Operation>]) -> bool {
     })
 }
 
-fn parse_input(input: &str) -> Vec<(i64, Vec<i64>)> {
-    input
-        .lines()
-        .map(|line| {
-            let (sum, values) = line.split_once(": ").unwrap();
-            (
-                sum.parse().unwrap(),
-                values
-                    .split_whitespace()
-                    .map(|x| x.parse().unwrap())
-                    .collect(),
-            )
+fn solve_part_1_forward(input: &str) -> i64 {
+    parse_input(input)
+        .unwrap()
+        .iter()
+        .filter(|(target, values)| is_reachable(*target, values, false))
+        .fold(0, |sum, (value, _)| sum + value)
+}
+
+fn solve_part_2_forward(input: &str) -> i64 {
+    parse_input(input)
+        .unwrap()
+        .iter()
+        .filter(|(target, values)| is_reachable(*target, values, true))
+        .fold(0, |sum, (value, _)| sum + value)
+}
+
+/// Builds the set of every value reachable from the left, dropping anything that
+/// overshoots the target since no operator can make a value smaller again.
+fn is_reachable(target: i64, values: &[i64], concatenate: bool) -> bool {
+    let mut reachable = HashSet::from([values[0]]);
+
+    for &value in &values[1..] {
+        let mut next = HashSet::new();
+
+        for &current in &reachable {
+            let mut results = vec![current + value, current * value];
+            if concatenate {
+                results.extend(concat(current, value));
+            }
+            next.extend(results.into_iter().filter(|&result| result <= target));
+        }
+
+        reachable = next;
+    }
+
+    reachable.contains(&target)
+}
+
+/// Writes the digits of `b` after those of `a`, unless that overflows.
+fn concat(a: i64, b: i64) -> Option<i64> {
+    i64::try_from(math::concat(a as u64, b as u64)?).ok()
+}
+
+fn parse_input(input: &str) -> Result<Vec<(i64, Vec<i64>)>> {
+    parse::parse_lines_with(input, |line| {
+        let mut scanner = Scanner::new(line);
+        let sum = scanner.integer::<i64>()?;
+        scanner.literal(": ")?;
+        let values = scanner.separated::<u16>(" ")?;
+        scanner.finish()?;
+
+        if sum < 0 || values.contains(&0) {
+            bail!("expected a target and positive values but found {:?}", line);
+        }
+
+        Ok((sum, values.into_iter().map(i64::from).collect()))
+    })
+}
+
+/// Generates `size` equations, built by applying random operators so that
+/// some of them are solvable.
+fn generate(size: usize, rng: &mut Rng) -> String {
+    (0..size)
+        .map(|_| {
+            let values: Vec<i64> = (0..rng.range(3..8))
+                .map(|_| rng.range(1..20) as i64)
+                .collect();
+            let mut target = values[0];
+            for &value in &values[1..] {
+                target = match rng.range(0..3) {
+                    0 => target + value,
+                    1 => target * value,
+                    _ => concat(target, value).unwrap(),
+                };
+            }
+            let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+            format!("{}: {}", target, values.join(" "))
         })
-        .collect()
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::years::y2024::day07::{solve_part_1, solve_part_2};
+    use crate::years::y2024::day07::{
+        solve_part_1, solve_part_1_forward, solve_part_2, solve_part_2_forward,
+    };
     use std::fs;
 
     #[test]
@@ -159,4 +224,16 @@ mod tests {
         let input = fs::read_to_string("./src/years/y2024/day07.txt").unwrap();
         assert_eq!(solve_part_2(&input), 165278151522644);
     }
+
+    #[test]
+    fn test_part_1_forward() {
+        let input = fs::read_to_string("./src/years/y2024/day07.txt").unwrap();
+        assert_eq!(solve_part_1_forward(&input), 1582598718861);
+    }
+
+    #[test]
+    fn test_part_2_forward() {
+        let input = fs::read_to_string("./src/years/y2024/day07.txt").unwrap();
+        assert_eq!(solve_part_2_forward(&input), 165278151522644);
+    }
 }
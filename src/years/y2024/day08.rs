@@ -1,22 +1,23 @@
+use crate::util::geometry::Point;
+use crate::util::grid::Grid;
+use crate::util::runner::Runner;
+use crate::util::shape::Shape;
+use anyhow::{bail, Result};
 use std::collections::HashMap;
-use std::fs;
 
-pub fn solver() {
-    println!("Day 8:");
-
-    let input = fs::read_to_string("./src/years/y2024/day08.txt")
-        .expect("Should have been able to read the file");
-
-    println!("Part 1: {}", solve_part_1(input.as_str()));
-    println!("Part 2: {}", solve_part_2(input.as_str()));
+pub fn runner() -> Runner {
+    Runner::new(2024, 8)
+        .shape(Shape::Grid("[.0-9A-Za-z]"))
+        .parser(|input| parse_input(input).map(drop))
+        .part1("default", |input, _| solve_part_1(input).into())
+        .part2("default", |input, _| solve_part_2(input).into())
 }
 
-type Point = (i32, i32);
-type Grid = HashMap<Point, char>;
-type Antennas = HashMap<char, Vec<Point>>;
+type Position = Point<i32>;
+type Antennas = HashMap<char, Vec<Position>>;
 
 fn solve_part_1(input: &str) -> usize {
-    let (mut grid, antennas) = parse_input(input);
+    let (mut grid, antennas) = parse_input(input).unwrap();
     let mut num_antinodes = 0;
 
     for (_, points) in antennas.iter() {
@@ -27,9 +28,11 @@ fn solve_part_1(input: &str) -> usize {
                 }
                 let antinode = calculate_antinode(point_a, point_b);
 
-                if grid.contains_key(&antinode) && grid[&antinode] != '#' {
-                    grid.insert(antinode, '#');
-                    num_antinodes += 1;
+                if let Some(cell) = grid.get_point_mut(antinode) {
+                    if *cell != '#' {
+                        *cell = '#';
+                        num_antinodes += 1;
+                    }
                 }
             }
         }
@@ -39,7 +42,7 @@ fn solve_part_1(input: &str) -> usize {
 }
 
 fn solve_part_2(input: &str) -> usize {
-    let (mut grid, antennas) = parse_input(input);
+    let (mut grid, antennas) = parse_input(input).unwrap();
     let mut num_antinodes = 0;
 
     for (_, points) in antennas.iter() {
@@ -49,15 +52,15 @@ fn solve_part_2(input: &str) -> usize {
                 if a == b {
                     continue
                 }
-                let delta = calculate_antinode_delta(point_a, point_b);
-                let mut current = add_points(delta, point_a);
+                let delta = point_a - point_b;
+                let mut current = point_a + delta;
 
-                while grid.contains_key(&current) {
-                    if grid[&current] == '.' {
-                        grid.insert(current, '#');
+                while let Some(cell) = grid.get_point_mut(current) {
+                    if *cell == '.' {
+                        *cell = '#';
                         num_antinodes += 1;
                     }
-                    current = add_points(delta, current);
+                    current += delta;
                 }
             }
         }
@@ -66,48 +69,26 @@ fn solve_part_2(input: &str) -> usize {
     num_antinodes
 }
 
-fn add_points(a: Point, b: Point) -> Point {
-    (a.0 + b.0, a.1 + b.1)
-}
-
-fn calculate_antinode(point: Point, other: Point) -> Point {
-    let (diff_y, diff_x) = calculate_antinode_delta(point, other);
-    (point.0 + diff_y, point.1 + diff_x)
-}
-
-fn calculate_antinode_delta(point: Point, other: Point) -> Point {
-    let diff_y = point.0 - other.0;
-    let diff_x = point.1 - other.1;
-
-    (diff_y, diff_x)
+fn calculate_antinode(point: Position, other: Position) -> Position {
+    point + (point - other)
 }
 
-fn parse_input(input: &str) -> (Grid, Antennas) {
-    let mut grid = Grid::new();
+fn parse_input(input: &str) -> Result<(Grid<char>, Antennas)> {
+    let grid = Grid::parse_with(input.trim(), |c| match c {
+        '.' => Ok(c),
+        c if c.is_ascii_alphanumeric() => Ok(c),
+        _ => bail!("expected '.' or an antenna"),
+    })?;
     let mut antennas = Antennas::new();
 
-    for (y, line) in input.trim().lines().enumerate() {
-        for (x, char) in line.chars().enumerate() {
-            grid.insert(point(x, y), char);
-
-            match char {
-                '.' => {}
-                c => {
-                    if antennas.contains_key(&c) {
-                        antennas.get_mut(&c).unwrap().push(point(x, y));
-                    } else {
-                        antennas.insert(c, vec![point(x, y)]);
-                    }
-                }
-            }
+    for (position, &c) in grid.iter() {
+        if c != '.' {
+            let position = Position::from_position(position).unwrap();
+            antennas.entry(c).or_default().push(position);
         }
     }
 
-    (grid, antennas)
-}
-
-fn point(x: usize, y: usize) -> Point {
-    (x as i32, y as i32)
+    Ok((grid, antennas))
 }
 
 #[cfg(test)]
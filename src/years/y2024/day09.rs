@@ -1,18 +1,27 @@
+use crate::event;
+use crate::util::intervals::IntervalSet;
+use crate::util::rng::Rng;
+use crate::util::runner::Runner;
+use crate::util::shape::Shape;
+use crate::util::trace;
+use crate::util::trace::Level;
+use anyhow::{anyhow, bail, Result};
 use std::collections::HashSet;
-use std::fs;
+use std::time::Duration;
 
-pub fn solver() {
-    println!("Day 9:");
-
-    let input = fs::read_to_string("./src/years/y2024/day09.txt")
-        .expect("Should have been able to read the file");
-
-    println!("Part 1: {}", solve_part_1(input.as_str()));
-    println!("Part 2: {}", solve_part_2(input.as_str()));
+pub fn runner() -> Runner {
+    Runner::new(2024, 9)
+        .shape(Shape::Line("[0-9]+"))
+        .parser(|input| to_blocks(input).map(drop))
+        .part1("default", |input, _| solve_part_1(input).into())
+        .part2("default", |input, _| solve_part_2(input).into())
+        .generator(generate)
+        .budget(1, Duration::from_millis(50))
+        .budget(2, Duration::from_millis(400))
 }
 
 fn solve_part_1(input: &str) -> usize {
-    let blocks = to_blocks(input);
+    let blocks = to_blocks(input).unwrap();
     let compacted = compact(blocks);
     compacted
         .iter()
@@ -21,7 +30,7 @@ fn solve_part_1(input: &str) -> usize {
 }
 
 fn solve_part_2(input: &str) -> usize {
-    let blocks = to_blocks(input);
+    let blocks = to_blocks(input).unwrap();
     let compacted = compact_2(blocks);
     compacted.iter().enumerate().fold(0, |checksum, (i, id)| {
         checksum
@@ -63,6 +72,10 @@ fn compact_2(blocks: Vec<String>) -> Vec<String> {
     let mut cursor = blocks.len() - 1;
     let mut result: Vec<String> = blocks.clone();
     let mut visited = HashSet::new();
+    let mut free: IntervalSet<usize> = (0..blocks.len())
+        .filter(|&i| blocks[i] == ".")
+        .map(|i| i..i + 1)
+        .collect();
 
     while cursor > 0 {
         // Find end of file to move
@@ -84,30 +97,29 @@ fn compact_2(blocks: Vec<String>) -> Vec<String> {
         }
 
         visited.insert(current_id.clone());
+        trace::count("files considered", 1);
 
-        // Find leftmost slot of free space that fits the file
-        let file_size = end_index - cursor;
-        let mut free_slot_start = 0;
-
-        while free_slot_start < cursor {
-            while free_slot_start < cursor && result[free_slot_start] != "." {
-                free_slot_start += 1;
-            }
-
-            let mut free_slot_end = free_slot_start;
-            while free_slot_end < cursor && result[free_slot_end + 1] == "." {
-                free_slot_end += 1;
-            }
+        // Find leftmost slot of free space that fits the file. Free space never
+        // overlaps the file, so a slot that starts before it also ends before it
+        let file = cursor..end_index + 1;
+        let slot = free
+            .first_fit(file.len())
+            .filter(|slot| slot.start < cursor);
 
-            if (free_slot_end - free_slot_start) >= file_size {
-                // Move
-                for i in 0..=file_size {
-                    result.swap(free_slot_start + i, cursor + i);
-                }
-                break;
+        if let Some(slot) = slot {
+            for i in 0..file.len() {
+                result.swap(slot.start + i, cursor + i);
             }
-
-            free_slot_start = free_slot_end + 1;
+            free.remove(slot.start..slot.start + file.len());
+            free.insert(file);
+            trace::count("files moved", 1);
+            event!(
+                Level::Trace,
+                "Moved file {} from {} to {}",
+                current_id,
+                cursor,
+                slot.start
+            );
         }
 
         if cursor == 0 {
@@ -120,27 +132,49 @@ fn compact_2(blocks: Vec<String>) -> Vec<String> {
     result
 }
 
-fn to_blocks(input: &str) -> Vec<String> {
-    input
-        .chars()
-        .enumerate()
-        .map(|(i, char)| {
-            let digit = char.to_digit(10).unwrap();
+fn to_blocks(input: &str) -> Result<Vec<String>> {
+    let mut blocks = Vec::new();
+
+    for (i, char) in input.chars().enumerate() {
+        let digit = char
+            .to_digit(10)
+            .ok_or_else(|| anyhow!("unexpected {:?} at {}", char, i))?;
+
+        if i % 2 != 0 {
+            blocks.extend(vec![".".to_string(); digit as usize]);
+        } else if digit == 0 {
+            bail!("file {} at {} is empty", i / 2, i);
+        } else {
+            let id = format!("{}", i / 2);
+            blocks.extend(vec![id.to_string(); digit as usize]);
+        }
+    }
+
+    if blocks.is_empty() {
+        bail!("the disk map is empty");
+    }
 
-            if i % 2 != 0 {
-                vec![".".to_string(); digit as usize]
+    Ok(blocks)
+}
+
+/// Generates a disk map with `size` files.
+fn generate(size: usize, rng: &mut Rng) -> String {
+    (0..size)
+        .map(|i| {
+            let file = rng.range(1..10);
+            if i + 1 < size {
+                format!("{}{}", file, rng.range(0..10))
             } else {
-                let id = format!("{}", i / 2);
-                vec![id.to_string(); digit as usize]
+                file.to_string()
             }
         })
-        .flatten()
         .collect()
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::years::y2024::day09::{solve_part_1, solve_part_2};
+    use crate::util::check::agree;
+    use crate::years::y2024::day09::{compact_2, solve_part_1, solve_part_2, to_blocks};
     use std::fs;
 
     #[test]
@@ -166,4 +200,61 @@ mod tests {
         let input = fs::read_to_string("./src/years/y2024/day09.txt").unwrap();
         assert_eq!(solve_part_2(&input), 6349492251099);
     }
+
+    /// Files are at least one block long, gaps at most nine.
+    fn normalize(disk_map: &[(u8, u8)]) -> Vec<(usize, usize)> {
+        disk_map
+            .iter()
+            .map(|(file, free)| (1 + *file as usize % 9, *free as usize % 10))
+            .collect()
+    }
+
+    /// Moves whole files the obvious way: from the highest id down, scan the disk
+    /// from the start for the first gap left of the file that is large enough.
+    fn compact_files(disk_map: &[(usize, usize)]) -> Vec<String> {
+        let mut blocks: Vec<Option<usize>> = Vec::new();
+        for (id, (file, free)) in disk_map.iter().enumerate() {
+            blocks.extend(vec![Some(id); *file]);
+            blocks.extend(vec![None; *free]);
+        }
+
+        for (id, (size, _)) in disk_map.iter().enumerate().rev() {
+            let start = blocks.iter().position(|block| *block == Some(id)).unwrap();
+            let gap = (0..start).find(|&i| {
+                i + size <= start && blocks[i..i + size].iter().all(|block| block.is_none())
+            });
+
+            if let Some(gap) = gap {
+                for i in 0..*size {
+                    blocks[gap + i] = Some(id);
+                    blocks[start + i] = None;
+                }
+            }
+        }
+
+        blocks
+            .iter()
+            .map(|block| block.map_or(".".to_string(), |id| id.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_compact_2_against_brute_force() {
+        agree(
+            500,
+            |rng| {
+                (0..rng.range(1..12))
+                    .map(|_| (rng.range(1..10) as u8, rng.range(0..10) as u8))
+                    .collect::<Vec<_>>()
+            },
+            |disk_map| compact_files(&normalize(disk_map)),
+            |disk_map| {
+                let input: String = normalize(disk_map)
+                    .iter()
+                    .map(|(file, free)| format!("{}{}", file, free))
+                    .collect();
+                compact_2(to_blocks(&input).unwrap())
+            },
+        );
+    }
 }
@@ -1,126 +1,90 @@
-use std::collections::HashSet;
-use std::fs;
-
-pub fn solver() {
-    println!("Day 10:");
-
-    let input = fs::read_to_string("./src/years/y2024/day09.txt")
-        .expect("Should have been able to read the file");
-
-    println!("Part 1: {}", solve_part_1(&input));
-    println!("Part 2: {}", solve_part_2(&input));
-}
-
-type Point = (i32, i32);
-
-fn get_value(map: &Vec<Vec<u32>>, position: Point) -> u32 {
-    map[position.1 as usize][position.0 as usize]
-}
-
-fn add_points(a: Point, b: Point) -> Point {
-    (a.0 + b.0, a.1 + b.1)
-}
-
-fn out_of_bounds(map: &Vec<Vec<u32>>, position: Point) -> bool {
-    let (x, y) = position;
-    x < 0 || y < 0 || x >= map.len() as i32 || y >= map[0].len() as i32
+use crate::util::bitset::BitGrid;
+use crate::util::grid::{Grid, Position};
+use crate::util::memo::Memo;
+use crate::util::rng::Rng;
+use crate::util::runner::Runner;
+use crate::util::shape::Shape;
+use anyhow::{anyhow, Result};
+
+pub fn runner() -> Runner {
+    Runner::new(2024, 10)
+        .shape(Shape::Grid("[0-9]"))
+        .parser(|input| parse_input(input).map(drop))
+        .part1("default", |input, _| solve_part_1(input).into())
+        .part2("default", |input, _| solve_part_2(input).into())
+        .generator(generate)
 }
 
-fn get_visited_points(
-    map: &Vec<Vec<u32>>,
-    visited: &mut HashSet<Point>,
-    point: Point,
-    last_value: i32,
-) {
-    if out_of_bounds(map, point) {
-        return;
-    }
-
-    let current_value = get_value(map, point);
-
-    if current_value != (last_value + 1) as u32 {
-        return;
-    }
-
-    visited.insert(point);
-
-    if current_value == 9 {
-        return;
-    }
+fn solve_part_1(input: &str) -> usize {
+    let map = parse_input(input).unwrap();
+
+    // The same visited set serves every trailhead, cleared in between
+    let mut seen = BitGrid::new(map.width(), map.height());
+    let mut stack = Vec::new();
+    trailheads(&map)
+        .map(|trailhead| {
+            seen.clear();
+            seen.insert(trailhead);
+            stack.push(trailhead);
+            while let Some(position) = stack.pop() {
+                stack.extend(uphill(&map, position).filter(|&next| seen.insert(next)));
+            }
 
-    [(1, 0), (-1, 0), (0, 1), (0, -1)]
-        .map(|dir| add_points(dir, point))
-        .into_iter()
-        .filter(|pos| !out_of_bounds(map, *pos))
-        .for_each(|pos| get_visited_points(map, visited, pos, current_value as i32))
+            seen.positions()
+                .filter(|&position| map[position] == 9)
+                .count()
+        })
+        .sum()
 }
 
-fn get_trailhead_rating(map: &Vec<Vec<u32>>, point: Point, last_value: i32) -> usize {
-    if out_of_bounds(map, point) {
-        return 0;
-    }
-
-    let current_value = get_value(map, point);
-
-    if current_value != (last_value + 1) as u32 {
-        return 0;
-    }
-
-    if current_value == 9 {
-        return 1;
-    }
-
-    [(1, 0), (-1, 0), (0, 1), (0, -1)]
-        .map(|dir| add_points(dir, point))
-        .into_iter()
-        .filter(|pos| !out_of_bounds(map, *pos))
-        .fold(0, |sum, pos| {
-            sum + get_trailhead_rating(map, pos, current_value as i32)
+fn solve_part_2(input: &str) -> u64 {
+    let map = parse_input(input).unwrap();
+
+    // Trails from different trailheads soon meet, so the rating of every
+    // position is shared between all of them
+    let mut ratings = Memo::new();
+    trailheads(&map)
+        .map(|trailhead| {
+            ratings.solve(trailhead, &|rating, position| {
+                if map[position] == 9 {
+                    1
+                } else {
+                    uphill(&map, position).map(rating).sum()
+                }
+            })
         })
+        .sum()
 }
 
-fn solve_part_1(input: &str) -> usize {
-    let input = parse_input(input);
-    let mut scores = Vec::new();
-
-    for (y, row) in input.iter().enumerate() {
-        for (x, &value) in row.iter().enumerate() {
-            if value == 0 {
-                let mut visited = HashSet::new();
-                get_visited_points(&input, &mut visited, (x as i32, y as i32), -1);
-                scores.push(
-                    visited
-                        .iter()
-                        .filter(|(x, y)| input[*y as usize][*x as usize] == 9)
-                        .count(),
-                );
-            }
-        }
-    }
-
-    scores.iter().sum()
+fn trailheads(map: &Grid<u32>) -> impl Iterator<Item = Position> + '_ {
+    map.iter()
+        .filter(|(_, &height)| height == 0)
+        .map(|(position, _)| position)
 }
 
-fn solve_part_2(input: &str) -> usize {
-    let input = parse_input(input);
-    let mut scores = Vec::new();
-
-    for (y, row) in input.iter().enumerate() {
-        for (x, &value) in row.iter().enumerate() {
-            if value == 0 {
-                scores.push(get_trailhead_rating(&input, (x as i32, y as i32), -1));
-            }
-        }
-    }
+/// The neighbours of a position that are exactly one higher.
+fn uphill(map: &Grid<u32>, position: Position) -> impl Iterator<Item = Position> + '_ {
+    map.neighbours4(position)
+        .filter(move |&next| map[next] == map[position] + 1)
+}
 
-    scores.iter().sum()
+fn parse_input(input: &str) -> Result<Grid<u32>> {
+    Grid::parse_with(input, |c| {
+        c.to_digit(10).ok_or_else(|| anyhow!("expected a height"))
+    })
 }
 
-fn parse_input(input: &str) -> Vec<Vec<u32>> {
-    input
-        .lines()
-        .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()).collect())
-        .collect()
+/// Generates a square topographic map with roughly `size` cells.
+fn generate(size: usize, rng: &mut Rng) -> String {
+    let side = (size as f64).sqrt().max(2.0) as usize;
+    (0..side)
+        .map(|_| {
+            (0..side)
+                .map(|_| char::from(b'0' + rng.range(0..10) as u8))
+                .collect()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
 }
 
 #[cfg(test)]
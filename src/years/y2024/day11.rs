@@ -1,57 +1,55 @@
-use std::collections::HashMap;
+use crate::util::counter::Counter;
+use crate::util::math::{digits, split_digits};
+use crate::util::runner::Runner;
+use crate::util::shape::Shape;
+use anyhow::Result;
 use std::iter::successors;
 
-type Stone = usize;
+type Stone = u64;
 
-pub fn solver() {
-    println!("Day 10:");
-
-    let input = "0 27 5409930 828979 4471 3 68524 170";
-    println!("Part 1: {}", solve(&input, 25));
-    println!("Part 2: {}", solve(&input, 75));
+pub fn runner() -> Runner {
+    Runner::new(2024, 11)
+        .shape(Shape::Line(r"\d+( \d+)*"))
+        .parser(|input| parse_input(input).map(drop))
+        .part1("default", |input, _| solve(input, 25).into())
+        .part2("default", |input, _| solve(input, 75).into())
 }
 
-fn parse_input(input: &str) -> HashMap<Stone, usize> {
-    input
+fn parse_input(input: &str) -> Result<Counter<Stone>> {
+    let stones = input
         .split(' ')
-        .map(|x| x.parse::<usize>().unwrap())
-        .fold(HashMap::new(), |mut map, stone| {
-            *map.entry(stone).or_default() += 1;
-            map
-        })
+        .map(|x| x.parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(stones.into_iter().map(Stone::from).collect())
 }
 
 fn solve(input: &str, blinks: usize) -> usize {
-    successors(Some(parse_input(input)), |stones| {
+    successors(Some(parse_input(input).unwrap()), |stones| {
         Some(process_stones(stones))
     })
     .nth(blinks)
     .unwrap()
-    .values()
-    .sum()
+    .total()
 }
 
-fn process_stones(stones: &HashMap<Stone, usize>) -> HashMap<Stone, usize> {
-    let mut result = HashMap::new();
+fn process_stones(stones: &Counter<Stone>) -> Counter<Stone> {
+    let mut result = Counter::new();
 
-    for (stone, count) in stones {
-        if *stone == 0 {
-            *result.entry(1).or_default() += count;
-        } else if stone.ilog10() % 2 == 1 {
-            let (a, b) = split(*stone);
-            *result.entry(a).or_default() += count;
-            *result.entry(b).or_default() += count;
+    for (&stone, count) in stones.iter() {
+        if stone == 0 {
+            result.add(1, count);
+        } else if digits(stone).is_multiple_of(2) {
+            let (a, b) = split_digits(stone, digits(stone) / 2);
+            result.add(a, count);
+            result.add(b, count);
         } else {
-            *result.entry(*stone * 2024).or_default() += count;
+            result.add(stone * 2024, count);
         }
     }
 
     result
 }
-fn split(value: usize) -> (usize, usize) {
-    let div = 10usize.pow((value.ilog10() + 1) / 2);
-    (value / div, value % div)
-}
 
 #[cfg(test)]
 mod tests {
@@ -1,12 +1,37 @@
-use regex::{Error, Regex};
+use crate::util::linear;
+use crate::util::linear::{Family, IntegerLine, Solutions};
+use crate::util::parse;
+use crate::util::rational::Rational;
+use crate::util::runner::Runner;
+use crate::util::shape::Shape;
+use anyhow::{bail, Result};
+use std::cmp::Ordering;
 use std::str::FromStr;
+use std::time::Duration;
+
+pub fn runner() -> Runner {
+    Runner::new(2024, 13)
+        .shape(Shape::Blocks(Box::new(Shape::Lines(
+            r"Button [AB]: X\+\d+, Y\+\d+|Prize: X=\d+, Y=\d+",
+        ))))
+        .parser(|input| parse_input(input).map(drop))
+        .part1("default", |input, _| part1(input).into())
+        .part2("default", |input, _| part2(input).into())
+        .budget(1, Duration::from_millis(100))
+        .budget(2, Duration::from_millis(100))
+}
 
 fn part1(input: &str) -> isize {
-    parse_input(input).into_iter().map(|m| solve(m, 0)).sum()
+    parse_input(input)
+        .unwrap()
+        .into_iter()
+        .map(|m| solve(m, 0))
+        .sum()
 }
 
 fn part2(input: &str) -> isize {
     parse_input(input)
+        .unwrap()
         .into_iter()
         .map(|m| solve(m, 10_000_000_000_000))
         .sum()
@@ -18,24 +43,57 @@ fn solve(machine: Machine, offset: isize) -> isize {
     let px = machine.prize_location.0 + offset;
     let py = machine.prize_location.1 + offset;
 
-    let d = by * ax - bx * ay;
-    let da = by * px - bx * py;
-    let db = ax * py - ay * px;
+    let cost = match linear::solve(&[vec![ax, bx], vec![ay, by]], &[px, py]) {
+        Solutions::None => None,
+        // Buttons can't be pressed a negative or fractional number of times
+        Solutions::Unique(presses) => match (presses[0].to_integer(), presses[1].to_integer()) {
+            (Some(a), Some(b)) if a >= 0 && b >= 0 => Some(3 * a + b),
+            _ => None,
+        },
+        // Both buttons move along the same line as the prize
+        Solutions::Infinite(presses) => cheapest_on_line(&presses),
+    };
 
-    if d == 0 {
-        0
-    } else if da % d == 0 && db % d == 0 {
-        3 * (da / d) + db / d
-    } else {
-        0
+    cost.unwrap_or(0) as isize
+}
+
+/// The cheapest presses when there are infinitely many ways to reach the prize.
+fn cheapest_on_line(presses: &Family) -> Option<i128> {
+    if presses.directions.len() > 1 {
+        // Neither button moves, so the prize is where the claw starts
+        return Some(0);
     }
+
+    // Every solution is start + k * step, and both presses can't be negative,
+    // which bounds k from both sides
+    let IntegerLine { start, step } = presses.integer_line()?;
+    let mut lowest = i128::MIN;
+    let mut highest = i128::MAX;
+    for (&start, &step) in start.iter().zip(&step) {
+        match step.cmp(&0) {
+            Ordering::Greater => lowest = lowest.max(Rational::new(-start, step).ceil()),
+            Ordering::Less => highest = highest.min(Rational::new(start, -step).floor()),
+            Ordering::Equal if start < 0 => return None,
+            Ordering::Equal => {}
+        }
+    }
+    if lowest > highest {
+        return None;
+    }
+
+    // The cost changes linearly with k, so the cheapest is at one of the ends.
+    // Both costs are positive, so the end moving towards cheaper presses is
+    // always bounded
+    let k = if 3 * step[0] + step[1] > 0 {
+        lowest
+    } else {
+        highest
+    };
+    Some(3 * (start[0] + k * step[0]) + start[1] + k * step[1])
 }
 
-fn parse_input(input: &str) -> Vec<Machine> {
-    input
-        .split("\n\n")
-        .map(|chunk| Machine::from_str(chunk).unwrap())
-        .collect()
+fn parse_input(input: &str) -> Result<Vec<Machine>> {
+    parse::blocks(input).map(Machine::from_str).collect()
 }
 
 type Point = (isize, isize);
@@ -48,37 +106,36 @@ struct Machine {
 }
 
 impl FromStr for Machine {
-    type Err = Error;
+    type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lines = s.trim().lines().collect::<Vec<_>>();
-        let (_, a) = lines[0].split_once(": ").unwrap();
-        let (_, b) = lines[1].split_once(": ").unwrap();
-        let (_, prize) = lines[2].split_once(": ").unwrap();
-
-        Ok(Machine {
-            button_a: parse_point(a),
-            button_b: parse_point(b),
-            prize_location: parse_point(prize),
-        })
-    }
-}
+        let values = s
+            .lines()
+            .map(|line| {
+                let (_, values) = parse::key_value(line)?;
+                let [x, y] = parse::exact_integers::<u32, 2>(values)?;
+                Ok((x as isize, y as isize))
+            })
+            .collect::<Result<Vec<_>>>()?;
 
-fn parse_point(input: &str) -> Point {
-    let regex = Regex::new(r"\d+").unwrap();
-    match regex
-        .find_iter(&input)
-        .map(|m| m.as_str().parse::<isize>().unwrap())
-        .collect::<Vec<_>>()[..]
-    {
-        [a, b] => (a, b),
-        _ => panic!("Missing values for X and Y"),
+        match values[..] {
+            [button_a, button_b, prize_location] => Ok(Machine {
+                button_a,
+                button_b,
+                prize_location,
+            }),
+            _ => bail!(
+                "expected two buttons and a prize but found {} lines",
+                values.len()
+            ),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::util::check::agree;
     use std::fs;
 
     #[test]
@@ -88,7 +145,7 @@ Button A: X+94, Y+34
 Button B: X+22, Y+67
 Prize: X=8400, Y=5400"#
             .trim();
-        assert_eq!(part1(&input), 280);
+        assert_eq!(part1(input), 280);
     }
 
     #[test]
@@ -98,7 +155,7 @@ Button A: X+26, Y+66
 Button B: X+67, Y+21
 Prize: X=12748, Y=12176"#
             .trim();
-        assert_eq!(part1(&input), 0);
+        assert_eq!(part1(input), 0);
     }
 
     #[test]
@@ -108,7 +165,7 @@ Button A: X+17, Y+86
 Button B: X+84, Y+37
 Prize: X=7870, Y=6450"#
             .trim();
-        assert_eq!(part1(&input), 200);
+        assert_eq!(part1(input), 200);
     }
 
     #[test]
@@ -118,7 +175,7 @@ Button A: X+69, Y+23
 Button B: X+27, Y+71
 Prize: X=18641, Y=10279"#
             .trim();
-        assert_eq!(part1(&input), 0);
+        assert_eq!(part1(input), 0);
     }
 
     #[test]
@@ -132,4 +189,83 @@ Prize: X=18641, Y=10279"#
         let input = fs::read_to_string("./src/years/y2024/day13.txt").unwrap();
         assert_eq!(part2(&input), 103729094227877);
     }
+
+    #[test]
+    fn test_collinear_buttons() {
+        let input = r#"
+Button A: X+2, Y+2
+Button B: X+3, Y+3
+Prize: X=10, Y=10"#
+            .trim();
+        assert_eq!(part1(input), 8);
+
+        let input = r#"
+Button A: X+2, Y+2
+Button B: X+4, Y+4
+Prize: X=5, Y=5"#
+            .trim();
+        assert_eq!(part1(input), 0);
+    }
+
+    type Presses = ((u8, u8), (u8, u8), (u16, u16));
+
+    /// Buttons move between 1 and 99 in each direction.
+    fn normalize(&((ax, ay), (bx, by), (px, py)): &Presses) -> Machine {
+        Machine {
+            button_a: (1 + ax as isize % 99, 1 + ay as isize % 99),
+            button_b: (1 + bx as isize % 99, 1 + by as isize % 99),
+            prize_location: (px as isize, py as isize),
+        }
+    }
+
+    /// Tries every number of presses of button A.
+    fn cheapest(machine: &Machine) -> isize {
+        let (ax, ay) = machine.button_a;
+        let (bx, by) = machine.button_b;
+        let (px, py) = machine.prize_location;
+
+        (0..=px / ax)
+            .filter_map(|a| {
+                let rest = px - a * ax;
+                let b = rest / bx;
+                (rest % bx == 0 && a * ay + b * by == py).then_some(3 * a + b)
+            })
+            .min()
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn test_solve_against_brute_force() {
+        agree(
+            1000,
+            |rng| {
+                let (a, b) = if rng.range(0..3) == 0 {
+                    // Both buttons move along the same line
+                    let (u, v) = (rng.range(1..10), rng.range(1..10));
+                    let mut button = || {
+                        let i = rng.range(1..10);
+                        ((i * u - 1) as u8, (i * v - 1) as u8)
+                    };
+                    (button(), button())
+                } else {
+                    let mut button = || (rng.range(0..99) as u8, rng.range(0..99) as u8);
+                    (button(), button())
+                };
+                let prize = if rng.range(0..3) == 0 {
+                    // Reachable with a known number of presses
+                    let machine = normalize(&(a, b, (0, 0)));
+                    let (i, j) = (rng.range(0..100) as isize, rng.range(0..100) as isize);
+                    let x = i * machine.button_a.0 + j * machine.button_b.0;
+                    let y = i * machine.button_a.1 + j * machine.button_b.1;
+                    (x as u16, y as u16)
+                } else {
+                    let limit = *rng.choose(&[200, 20000]);
+                    (rng.range(0..limit) as u16, rng.range(0..limit) as u16)
+                };
+                (a, b, prize)
+            },
+            |presses| cheapest(&normalize(presses)),
+            |presses| solve(normalize(presses), 0),
+        );
+    }
 }
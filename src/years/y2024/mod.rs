@@ -1,3 +1,6 @@
+use crate::util::runner::Runner;
+use std::time::Duration;
+
 pub mod day01;
 pub mod day02;
 pub mod day03;
@@ -10,21 +13,24 @@ pub mod day09;
 pub mod day10;
 pub mod day11;
 pub mod day12;
-mod day13;
+pub mod day13;
+
+pub const TIME_LIMIT: Duration = Duration::from_secs(1);
 
-pub fn get_solver(day: u8) -> fn() {
-    match day {
-        1 => day01::solver,
-        2 => day02::solver,
-        3 => day03::solver,
-        4 => day04::solver,
-        5 => day05::solver,
-        6 => day06::solver,
-        7 => day07::solver,
-        8 => day08::solver,
-        9 => day09::solver,
-        10 => day10::solver,
-        11 => day11::solver,
-        _ => panic!("Unknown day: {}", day),
-    }
-}
\ No newline at end of file
+pub fn runners() -> Vec<Runner> {
+    vec![
+        day01::runner(),
+        day02::runner(),
+        day03::runner(),
+        day04::runner(),
+        day05::runner(),
+        day06::runner(),
+        day07::runner(),
+        day08::runner(),
+        day09::runner(),
+        day10::runner(),
+        day11::runner(),
+        day12::runner(),
+        day13::runner(),
+    ]
+}